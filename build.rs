@@ -0,0 +1,32 @@
+use std::{env, process::Command};
+
+fn main() {
+  println!("cargo:rerun-if-changed=build.rs");
+  println!("cargo:rustc-check-cfg=cfg(nightly)");
+  println!("cargo:rustc-check-cfg=cfg(std_absolute)");
+
+  // `std::path::absolute` was stabilized in Rust 1.79.
+  if minor_version().is_some_and(|minor| minor >= 79) {
+    println!("cargo:rustc-cfg=std_absolute");
+  }
+
+  // The `allocator_api` feature requires the unstable `allocator_api`
+  // language feature, so it is ignored on stable and beta toolchains.
+  if env::var_os("CARGO_FEATURE_ALLOCATOR_API").is_some() && is_nightly() {
+    println!("cargo:rustc-cfg=nightly");
+  }
+}
+
+fn minor_version() -> Option<u32> {
+  version()?.split('.').nth(1)?.parse().ok()
+}
+
+fn is_nightly() -> bool {
+  version().is_some_and(|version| version.contains("-nightly") || version.contains("-dev"))
+}
+
+fn version() -> Option<String> {
+  let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+  let output = Command::new(rustc).arg("--version").output().ok()?;
+  String::from_utf8(output.stdout).ok()
+}
@@ -0,0 +1,31 @@
+#ifndef LEXICLEAN_H
+#define LEXICLEAN_H
+
+/* C interface to lexiclean, available with the `ffi` feature. Paths are
+ * NUL-terminated POSIX paths, cleaned like `Lexiclean::lexiclean` on Unix. */
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Clean `input` into `out`, which is `out_len` bytes long, and
+ * NUL-terminate it. Returns the length of the cleaned path, not including
+ * the NUL, or -1 if `input` or `out` is null, or if `out` is too small. A
+ * buffer of `strlen(input) + 2` bytes is always large enough. */
+ptrdiff_t lexiclean_cstr(const char *input, char *out, size_t out_len);
+
+/* Clean `input`, returning a newly allocated cleaned path, which must be
+ * freed with `lexiclean_cstr_free`, or null if `input` is null. */
+char *lexiclean_cstr_alloc(const char *input);
+
+/* Free a path returned by `lexiclean_cstr_alloc`. Does nothing if `path` is
+ * null. */
+void lexiclean_cstr_free(char *path);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,216 @@
+use std::{
+  io,
+  path::{Path, PathBuf},
+};
+
+/// Make `path` absolute without accessing the filesystem, like
+/// `std::path::absolute`, for toolchains that predate it. When built with a
+/// toolchain that has `std::path::absolute`, it is called instead. Fails if
+/// `path` is empty, or if the current directory is needed and cannot be
+/// fetched.
+///
+/// Like `std::path::absolute`, this does not clean the path: on Unix, `.`
+/// components and repeated separators are removed, but `..` components and
+/// a trailing separator are kept, as are exactly two leading slashes. On
+/// Windows, where `std::path::absolute` calls `GetFullPathNameW`, `..`
+/// components are removed, and trailing dots and spaces are stripped from
+/// components, while verbatim paths are returned unchanged.
+///
+/// `Lexiclean::lexiclean_absolute` is the cleaning variant.
+///
+/// ```
+/// # #[cfg(unix)]
+/// assert_eq!(
+///   lexiclean::absolute("/foo/./bar/../").unwrap(),
+///   std::path::Path::new("/foo/bar/../"),
+/// );
+/// ```
+pub fn absolute(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+  let path = path.as_ref();
+
+  if path.as_os_str().is_empty() {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      "cannot make an empty path absolute",
+    ));
+  }
+
+  absolute_nonempty(path)
+}
+
+#[cfg(std_absolute)]
+#[clippy::msrv = "1.79"]
+fn absolute_nonempty(path: &Path) -> io::Result<PathBuf> {
+  std::path::absolute(path)
+}
+
+#[cfg(not(std_absolute))]
+fn absolute_nonempty(path: &Path) -> io::Result<PathBuf> {
+  polyfill(path)
+}
+
+#[cfg(all(not(windows), any(not(std_absolute), test)))]
+fn polyfill(path: &Path) -> io::Result<PathBuf> {
+  use std::env;
+
+  let bytes = path.as_os_str().as_encoded_bytes();
+
+  let mut components = path.strip_prefix(".").unwrap_or(path).components();
+
+  let mut absolute = if path.is_absolute() {
+    if bytes.starts_with(b"//") && !bytes.starts_with(b"///") {
+      components.next();
+      PathBuf::from("//")
+    } else {
+      PathBuf::new()
+    }
+  } else {
+    env::current_dir()?
+  };
+
+  absolute.extend(components);
+
+  if bytes.ends_with(b"/") {
+    absolute.push("");
+  }
+
+  Ok(absolute)
+}
+
+#[cfg(all(windows, any(not(std_absolute), test)))]
+fn polyfill(path: &Path) -> io::Result<PathBuf> {
+  use {
+    crate::{is_verbatim, Lexicleaner},
+    std::{
+      env,
+      path::{self, Component, Prefix},
+    },
+  };
+
+  if is_verbatim(path) {
+    return Ok(path.to_owned());
+  }
+
+  let disk = |path: &Path| match path.components().next() {
+    Some(Component::Prefix(prefix)) => match prefix.kind() {
+      Prefix::Disk(letter) => Some(letter),
+      _ => None,
+    },
+    _ => None,
+  };
+
+  let resolved = if path.is_absolute() {
+    path.to_owned()
+  } else {
+    let current_dir = env::current_dir()?;
+
+    match disk(path) {
+      // Drive-relative paths, like `C:foo`, are resolved against the current
+      // directory of their drive, which is stored in the `=C:` environment
+      // variable, or against the root of the drive if it has none.
+      Some(letter) => {
+        let dir = if disk(&current_dir) == Some(letter) {
+          current_dir
+        } else {
+          let letter = char::from(letter);
+          env::var_os(format!("={}:", letter))
+            .map_or_else(|| format!(r"{}:\", letter).into(), PathBuf::from)
+        };
+        dir.join(path.components().skip(1).collect::<PathBuf>())
+      }
+      None => current_dir.join(path),
+    }
+  };
+
+  let mut absolute = Lexicleaner::new()
+    .strip_trailing_dots_and_spaces(true)
+    .clean(resolved);
+
+  let trailing = |path: &Path| {
+    path
+      .as_os_str()
+      .as_encoded_bytes()
+      .last()
+      .is_some_and(|&byte| path::is_separator(byte.into()))
+  };
+
+  if trailing(path) && !trailing(&absolute) {
+    absolute.as_mut_os_string().push(path::MAIN_SEPARATOR_STR);
+  }
+
+  Ok(absolute)
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, std::env};
+
+  #[test]
+  fn empty_paths_are_rejected() {
+    assert_eq!(
+      absolute("").unwrap_err().kind(),
+      io::ErrorKind::InvalidInput
+    );
+  }
+
+  #[test]
+  fn relative_paths_are_resolved_against_current_dir() {
+    let current_dir = env::current_dir().unwrap();
+    assert_eq!(absolute("foo").unwrap(), current_dir.join("foo"));
+    assert_eq!(absolute(".").unwrap(), current_dir);
+    assert_eq!(
+      absolute("./foo/./bar").unwrap(),
+      current_dir.join("foo/bar")
+    );
+  }
+
+  #[test]
+  #[cfg(std_absolute)]
+  #[clippy::msrv = "1.79"]
+  fn matches_std() {
+    #[cfg(unix)]
+    let paths = [
+      "/",
+      "//",
+      "///",
+      "//foo",
+      "///foo",
+      "/foo/./bar",
+      "/foo//bar/",
+      "/foo/../bar",
+      "/foo/..",
+      "foo/../bar",
+      "./foo/",
+      "..",
+      ".",
+    ];
+
+    #[cfg(windows)]
+    let paths = [
+      r"C:\foo\..\bar",
+      r"C:\foo\.\bar\",
+      r"\foo\..\bar",
+      r"foo\..\bar",
+      "foo. ",
+      r"\\server\share\foo\..\bar",
+      r"\\?\C:\foo\..",
+      "..",
+      ".",
+    ];
+
+    for path in paths {
+      assert_eq!(
+        polyfill(Path::new(path)).unwrap(),
+        std::path::absolute(path).unwrap(),
+        "{}",
+        path
+      );
+      assert_eq!(
+        polyfill(Path::new(path)).unwrap().as_os_str(),
+        std::path::absolute(path).unwrap().as_os_str(),
+        "{}",
+        path
+      );
+    }
+  }
+}
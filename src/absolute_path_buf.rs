@@ -0,0 +1,269 @@
+use {
+  crate::{check_nul, Error, Lexiclean},
+  std::{
+    borrow::Borrow,
+    convert::TryFrom,
+    ops::Deref,
+    path::{Path, PathBuf},
+    str::FromStr,
+  },
+};
+
+/// An owned path which is always absolute and clean.
+///
+/// An `AbsolutePathBuf` is constructed from an absolute path with `new`, or
+/// from a relative path and an absolute base with `with_base`, and
+/// dereferences to `Path`.
+///
+/// ```
+/// use lexiclean::{AbsolutePathBuf, Error};
+/// use std::path::Path;
+///
+/// # #[cfg(unix)]
+/// # {
+/// let root = AbsolutePathBuf::new("/opt/app/./bin/..").unwrap();
+/// assert_eq!(root, Path::new("/opt/app"));
+///
+/// assert_eq!(root.join("../etc").unwrap(), Path::new("/opt/etc"));
+///
+/// assert_eq!(AbsolutePathBuf::new("opt/app"), Err(Error::Relative));
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AbsolutePathBuf {
+  path: PathBuf,
+}
+
+impl AbsolutePathBuf {
+  /// Clean `path`, failing with `Error::Relative` if it is not absolute,
+  /// with `Error::Escape` if a `..` would ascend above the root, and with
+  /// `Error::Nul` if it contains a NUL byte.
+  pub fn new(path: impl AsRef<Path>) -> Result<Self, Error> {
+    let path = path.as_ref();
+
+    if !path.is_absolute() {
+      return Err(Error::Relative);
+    }
+
+    check_nul(path)?;
+
+    Ok(Self {
+      path: path.lexiclean_strict()?,
+    })
+  }
+
+  /// Join `path` onto `base` and clean the result. If `path` is absolute, it
+  /// replaces `base`. Fails like `new` if `base` or the joined path would be
+  /// rejected, which happens on Windows if `path` is relative to the current
+  /// directory of a drive, like `C:foo`.
+  pub fn with_base(path: impl AsRef<Path>, base: impl AsRef<Path>) -> Result<Self, Error> {
+    let base = base.as_ref();
+
+    if !base.is_absolute() {
+      return Err(Error::Relative);
+    }
+
+    Self::new(base.join(path))
+  }
+
+  pub fn as_path(&self) -> &Path {
+    &self.path
+  }
+
+  pub fn into_path_buf(self) -> PathBuf {
+    self.path
+  }
+
+  /// Join `path` onto `self`, like `with_base`.
+  pub fn join(&self, path: impl AsRef<Path>) -> Result<Self, Error> {
+    Self::with_base(path, &self.path)
+  }
+}
+
+impl Deref for AbsolutePathBuf {
+  type Target = Path;
+
+  fn deref(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl AsRef<Path> for AbsolutePathBuf {
+  fn as_ref(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl Borrow<Path> for AbsolutePathBuf {
+  fn borrow(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl From<AbsolutePathBuf> for PathBuf {
+  fn from(path: AbsolutePathBuf) -> Self {
+    path.path
+  }
+}
+
+/// Parsing fails like `AbsolutePathBuf::new`.
+impl FromStr for AbsolutePathBuf {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Error> {
+    Self::new(s)
+  }
+}
+
+/// Conversion fails like `AbsolutePathBuf::new`.
+impl TryFrom<&Path> for AbsolutePathBuf {
+  type Error = Error;
+
+  fn try_from(path: &Path) -> Result<Self, Error> {
+    Self::new(path)
+  }
+}
+
+impl PartialEq<Path> for AbsolutePathBuf {
+  fn eq(&self, other: &Path) -> bool {
+    self.path == other
+  }
+}
+
+impl PartialEq<&Path> for AbsolutePathBuf {
+  fn eq(&self, other: &&Path) -> bool {
+    self.path == *other
+  }
+}
+
+impl PartialEq<PathBuf> for AbsolutePathBuf {
+  fn eq(&self, other: &PathBuf) -> bool {
+    self.path == *other
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn relative_paths_are_rejected() {
+    for path in ["", ".", "foo", "../foo"] {
+      assert_eq!(AbsolutePathBuf::new(path), Err(Error::Relative), "{}", path);
+    }
+    assert_eq!("foo".parse::<AbsolutePathBuf>(), Err(Error::Relative));
+  }
+
+  #[test]
+  fn relative_bases_are_rejected() {
+    assert_eq!(
+      AbsolutePathBuf::with_base("bin", "usr"),
+      Err(Error::Relative)
+    );
+    assert_eq!(AbsolutePathBuf::with_base("", ""), Err(Error::Relative));
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn absolute_paths_are_cleaned() {
+    assert_eq!(
+      AbsolutePathBuf::new("/foo/./bar/../baz/").unwrap(),
+      Path::new("/foo/baz")
+    );
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn invalid_paths_are_rejected() {
+    assert_eq!(AbsolutePathBuf::new("/.."), Err(Error::Escape));
+    assert_eq!(AbsolutePathBuf::new("/foo/../.."), Err(Error::Escape));
+    assert_eq!(AbsolutePathBuf::new("/foo\0"), Err(Error::Nul));
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn paths_are_parsed() {
+    assert_eq!(
+      "/foo/./bar".parse::<AbsolutePathBuf>().unwrap(),
+      Path::new("/foo/bar")
+    );
+    assert_eq!(
+      AbsolutePathBuf::try_from(Path::new("/..")),
+      Err(Error::Escape)
+    );
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn relative_paths_are_resolved_against_base() {
+    assert_eq!(
+      AbsolutePathBuf::with_base("../bin", "/usr/lib").unwrap(),
+      Path::new("/usr/bin")
+    );
+    assert_eq!(
+      AbsolutePathBuf::with_base("/etc", "/usr/lib").unwrap(),
+      Path::new("/etc")
+    );
+    assert_eq!(
+      AbsolutePathBuf::with_base("", "/usr/lib/").unwrap(),
+      Path::new("/usr/lib")
+    );
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn paths_are_joined() {
+    let root = AbsolutePathBuf::new("/srv").unwrap();
+    assert_eq!(root.join("www/..").unwrap(), Path::new("/srv"));
+    assert_eq!(root.join("..").unwrap(), Path::new("/"));
+    assert_eq!(root.join("../.."), Err(Error::Escape));
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn windows_paths_with_prefixes_are_absolute() {
+    assert_eq!(
+      AbsolutePathBuf::new(r"C:\foo\.\bar\..\baz\").unwrap(),
+      Path::new(r"C:\foo\baz")
+    );
+    assert_eq!(
+      AbsolutePathBuf::new(r"\\server\share\foo\bar\..").unwrap(),
+      Path::new(r"\\server\share\foo")
+    );
+    assert_eq!(AbsolutePathBuf::new(r"C:\.."), Err(Error::Escape));
+    assert_eq!(AbsolutePathBuf::new("C:\\foo\0"), Err(Error::Nul));
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn windows_paths_without_prefix_and_root_are_relative() {
+    for path in ["C:", "C:foo", r"C:..\foo", r"\foo", "/foo"] {
+      assert_eq!(AbsolutePathBuf::new(path), Err(Error::Relative), "{}", path);
+    }
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn windows_paths_are_resolved_against_base() {
+    assert_eq!(
+      AbsolutePathBuf::with_base(r"..\baz", r"C:\foo\bar").unwrap(),
+      Path::new(r"C:\foo\baz")
+    );
+    assert_eq!(
+      AbsolutePathBuf::with_base(r"\baz", r"C:\foo\bar").unwrap(),
+      Path::new(r"C:\baz")
+    );
+    assert_eq!(
+      AbsolutePathBuf::with_base(r"D:\baz", r"C:\foo").unwrap(),
+      Path::new(r"D:\baz")
+    );
+    assert_eq!(
+      AbsolutePathBuf::with_base("D:baz", r"C:\foo"),
+      Err(Error::Relative)
+    );
+    assert_eq!(
+      AbsolutePathBuf::with_base("bar", r"\foo"),
+      Err(Error::Relative)
+    );
+  }
+}
@@ -0,0 +1,147 @@
+use {
+  crate::Lexiclean,
+  std::path::{Component, Path, PathBuf},
+};
+
+/// A policy of allowed and denied path prefixes.
+///
+/// Prefixes and checked paths are compared by their cleaned forms with
+/// `Lexiclean::is_within`, so `/srv/../etc` is not under `/srv`, and
+/// `/srv/data` is not under `/srv/d`. The most specific matching prefix,
+/// the one with the most components, decides whether a path is allowed,
+/// with deny prefixes winning ties, and paths under no prefix are denied.
+/// As with `Jail`, checks are purely lexical, so symlinks are not followed.
+///
+/// ```
+/// use lexiclean::AccessPolicy;
+///
+/// let policy = AccessPolicy::new()
+///   .allow("/home/user")
+///   .deny("/home/user/.ssh")
+///   .allow("/home/user/.ssh/config");
+///
+/// assert!(policy.check("/home/user/notes.txt"));
+/// assert!(policy.check("/home/user/.ssh/config"));
+/// assert!(!policy.check("/home/user/.ssh/id_ed25519"));
+/// assert!(!policy.check("/home/user/../other/notes.txt"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessPolicy {
+  rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Rule {
+  allow: bool,
+  depth: usize,
+  prefix: PathBuf,
+}
+
+impl AccessPolicy {
+  /// A policy which denies every path.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Allow paths lexically inside, or equal to, `prefix`.
+  pub fn allow(self, prefix: impl AsRef<Path>) -> Self {
+    self.rule(prefix.as_ref(), true)
+  }
+
+  /// Deny paths lexically inside, or equal to, `prefix`.
+  pub fn deny(self, prefix: impl AsRef<Path>) -> Self {
+    self.rule(prefix.as_ref(), false)
+  }
+
+  fn rule(mut self, prefix: &Path, allow: bool) -> Self {
+    let prefix = prefix.lexiclean();
+
+    self.rules.push(Rule {
+      allow,
+      depth: prefix
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .count(),
+      prefix,
+    });
+
+    self
+  }
+
+  /// Returns `true` if `path` is allowed.
+  pub fn check(&self, path: impl AsRef<Path>) -> bool {
+    let path = path.as_ref().lexiclean();
+
+    self
+      .rules
+      .iter()
+      .filter(|rule| path.is_within(&rule.prefix))
+      .max_by_key(|rule| (rule.depth, !rule.allow))
+      .is_some_and(|rule| rule.allow)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn empty_policy_denies_everything() {
+    let policy = AccessPolicy::new();
+    for path in ["", ".", "/", "foo", "/foo"] {
+      assert!(!policy.check(path), "{}", path);
+    }
+  }
+
+  #[test]
+  fn prefixes_are_compared_by_component() {
+    let policy = AccessPolicy::new().allow("/srv/d");
+    assert!(policy.check("/srv/d"));
+    assert!(policy.check("/srv/d/"));
+    assert!(policy.check("/srv/d/file"));
+    assert!(!policy.check("/srv/data"));
+    assert!(!policy.check("/srv"));
+  }
+
+  #[test]
+  fn paths_and_prefixes_are_cleaned() {
+    let policy = AccessPolicy::new().allow("/srv/./www/");
+    assert!(policy.check("/srv/www/css/../index.html"));
+    assert!(policy.check("/srv//www"));
+    assert!(!policy.check("/srv/www/../secret"));
+    assert!(!policy.check("/srv/www/.."));
+  }
+
+  #[test]
+  fn most_specific_prefix_wins() {
+    let policy = AccessPolicy::new()
+      .deny("/")
+      .allow("/home")
+      .deny("/home/user/.ssh")
+      .allow("/home/user/.ssh/known_hosts");
+    assert!(!policy.check("/etc/passwd"));
+    assert!(policy.check("/home/user"));
+    assert!(!policy.check("/home/user/.ssh"));
+    assert!(!policy.check("/home/user/.ssh/id_rsa"));
+    assert!(policy.check("/home/user/.ssh/known_hosts"));
+  }
+
+  #[test]
+  fn deny_wins_ties() {
+    let policy = AccessPolicy::new().allow("/foo").deny("/foo/.");
+    assert!(!policy.check("/foo/bar"));
+
+    let policy = AccessPolicy::new().deny("/foo").allow("/foo");
+    assert!(!policy.check("/foo"));
+  }
+
+  #[test]
+  fn relative_prefixes() {
+    let policy = AccessPolicy::new().allow(".").deny("target");
+    assert!(policy.check("src/lib.rs"));
+    assert!(policy.check("./target/../README.md"));
+    assert!(!policy.check("target/debug"));
+    assert!(!policy.check("../other"));
+    assert!(!policy.check("/etc"));
+  }
+}
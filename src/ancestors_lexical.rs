@@ -0,0 +1,78 @@
+use std::path::{Component, PathBuf};
+
+/// Iterator over the cleaned form of a path and its ancestors, returned by
+/// `Lexiclean::ancestors_lexical`.
+///
+/// The path is cleaned once, and then each ancestor is produced by removing
+/// the final `Normal` component. Iteration ends at the root, at a path
+/// consisting only of `..` components, or at `.`.
+#[derive(Clone, Debug)]
+pub struct AncestorsLexical {
+  next: Option<PathBuf>,
+}
+
+impl AncestorsLexical {
+  pub(crate) fn new(cleaned: PathBuf) -> Self {
+    Self {
+      next: Some(cleaned),
+    }
+  }
+}
+
+impl Iterator for AncestorsLexical {
+  type Item = PathBuf;
+
+  fn next(&mut self) -> Option<PathBuf> {
+    let current = self.next.take()?;
+
+    if let Some(Component::Normal(_)) = current.components().next_back() {
+      let mut parent = current.clone();
+      parent.pop();
+      if parent.as_os_str().is_empty() {
+        parent.push(Component::CurDir);
+      }
+      self.next = Some(parent);
+    }
+
+    Some(current)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    crate::Lexiclean,
+    std::path::{Path, PathBuf},
+  };
+
+  #[track_caller]
+  fn case(path: &str, want: &[&str]) {
+    assert_eq!(
+      Path::new(path).ancestors_lexical().collect::<Vec<_>>(),
+      want.iter().map(PathBuf::from).collect::<Vec<_>>(),
+    );
+  }
+
+  #[test]
+  fn ancestors_are_cleaned() {
+    case("a/b/../c", &["a/c", "a", "."]);
+  }
+
+  #[test]
+  fn absolute_ancestors_end_at_root() {
+    case("/a/./b/", &["/a/b", "/a", "/"]);
+    case("/..", &["/"]);
+  }
+
+  #[test]
+  fn relative_ancestors_end_at_parent_dirs() {
+    case("../../a", &["../../a", "../.."]);
+    case("..", &[".."]);
+  }
+
+  #[test]
+  fn current_dir_has_no_ancestors() {
+    case("", &["."]);
+    case("a/..", &["."]);
+  }
+}
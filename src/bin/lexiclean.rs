@@ -0,0 +1,416 @@
+use {
+  lexiclean::{clean_join, for_each_delimited, Lexiclean, Posix, PurePath, Windows},
+  std::{
+    env,
+    ffi::OsString,
+    fmt::{self, Display, Formatter},
+    io::{self, BufRead, BufWriter, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process,
+  },
+};
+
+const USAGE: &str = "\
+Usage: lexiclean [OPTIONS] [PATH]...
+
+Lexically clean each PATH, or each line of standard input if no PATHs are
+given, and print the cleaned paths, one per line. Standard input is processed
+as it is read. Paths are cleaned without accessing the filesystem, like
+`realpath -m --no-symlinks`, except that relative paths are kept relative.
+
+Options:
+      --check       Print the paths which are not inside of the root, and
+                    exit with status 1 if there are any, instead of printing
+                    cleaned paths. Requires --root.
+  -h, --help        Print this message and exit
+  -0, --null        Separate input and output paths with NUL instead of
+                    newline, for use with `find -print0` and `xargs -0`
+      --posix       Clean POSIX paths, regardless of the host platform
+      --root <DIR>  Join each path onto DIR before cleaning it
+      --windows     Clean Windows paths, regardless of the host platform";
+
+#[derive(Debug)]
+enum Error {
+  Io(io::Error),
+  Unicode,
+  Usage(String),
+}
+
+impl Display for Error {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Io(error) => write!(f, "{}", error),
+      Self::Unicode => write!(f, "path is not valid UTF-8"),
+      Self::Usage(message) => write!(f, "{}\n\n{}", message, USAGE),
+    }
+  }
+}
+
+impl From<io::Error> for Error {
+  fn from(error: io::Error) -> Self {
+    Self::Io(error)
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Flavor {
+  Posix,
+  Windows,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Arguments {
+  check: bool,
+  flavor: Option<Flavor>,
+  help: bool,
+  null: bool,
+  paths: Vec<PathBuf>,
+  root: Option<PathBuf>,
+}
+
+impl Arguments {
+  fn parse(args: impl IntoIterator<Item = OsString>) -> Result<Self, Error> {
+    let mut arguments = Self::default();
+    let mut options = true;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+      let option = arg
+        .to_str()
+        .filter(|arg| options && arg.starts_with('-') && *arg != "-");
+
+      let option = match option {
+        Some(option) => option,
+        None => {
+          arguments.paths.push(arg.into());
+          continue;
+        }
+      };
+
+      match option {
+        "--" => options = false,
+        "--check" => arguments.check = true,
+        "-h" | "--help" => arguments.help = true,
+        "-0" | "--null" => arguments.null = true,
+        "--posix" => arguments.set_flavor(Flavor::Posix)?,
+        "--windows" => arguments.set_flavor(Flavor::Windows)?,
+        "--root" => {
+          let root = args
+            .next()
+            .ok_or_else(|| Error::Usage("`--root` requires a value".into()))?;
+          arguments.root = Some(root.into());
+        }
+        _ => match option.strip_prefix("--root=") {
+          Some(root) => arguments.root = Some(root.into()),
+          None => return Err(Error::Usage(format!("unknown option `{}`", option))),
+        },
+      }
+    }
+
+    if arguments.check && arguments.root.is_none() {
+      return Err(Error::Usage("`--check` requires `--root`".into()));
+    }
+
+    Ok(arguments)
+  }
+
+  fn delimiter(&self) -> u8 {
+    if self.null {
+      b'\0'
+    } else {
+      b'\n'
+    }
+  }
+
+  fn set_flavor(&mut self, flavor: Flavor) -> Result<(), Error> {
+    if self.flavor.is_some_and(|existing| existing != flavor) {
+      return Err(Error::Usage(
+        "`--posix` and `--windows` cannot be combined".into(),
+      ));
+    }
+    self.flavor = Some(flavor);
+    Ok(())
+  }
+}
+
+/// Returns `false` if `--check` found paths which are not inside of the root.
+/// If `interactive` is `true`, output is flushed after each input path.
+fn run(
+  args: impl IntoIterator<Item = OsString>,
+  stdin: impl BufRead,
+  stdout: impl Write,
+  interactive: bool,
+) -> Result<bool, Error> {
+  let arguments = Arguments::parse(args)?;
+
+  let mut stdout = BufWriter::new(stdout);
+
+  if arguments.help {
+    writeln!(stdout, "{}", USAGE)?;
+    stdout.flush()?;
+    return Ok(true);
+  }
+
+  let mut contained = true;
+
+  if arguments.paths.is_empty() {
+    for_each_delimited(stdin, arguments.delimiter(), |path| {
+      contained &= clean_path(
+        &arguments,
+        &mut stdout,
+        strip_carriage_return(&arguments, path),
+      )?;
+      if interactive {
+        stdout.flush()?;
+      }
+      Ok::<(), Error>(())
+    })?;
+  } else {
+    for path in &arguments.paths {
+      contained &= clean_path(&arguments, &mut stdout, path)?;
+    }
+  }
+
+  stdout.flush()?;
+
+  Ok(contained)
+}
+
+/// Clean `path` and print it, or with `--check`, print `path` if it is not
+/// inside of the root. Returns `false` if `path` is not inside of the root.
+fn clean_path(arguments: &Arguments, stdout: &mut impl Write, path: &Path) -> Result<bool, Error> {
+  let root = arguments.root.as_deref();
+
+  let (cleaned, contained) = match arguments.flavor {
+    Some(Flavor::Posix) => clean_pure::<Posix>(root, path)?,
+    Some(Flavor::Windows) => clean_pure::<Windows>(root, path)?,
+    None => {
+      let cleaned = match root {
+        Some(root) => clean_join(root, path),
+        None => path.lexiclean(),
+      };
+      let contained = root.map_or(true, |root| cleaned.is_within(root));
+      (cleaned, contained)
+    }
+  };
+
+  if !arguments.check {
+    write_path(stdout, &cleaned, arguments.delimiter())?;
+    return Ok(true);
+  }
+
+  if !contained {
+    write_path(stdout, path, arguments.delimiter())?;
+  }
+
+  Ok(contained)
+}
+
+/// Clean `path` with the flavored cleaning of `PurePath`, which requires
+/// `path` and `root` to be valid UTF-8.
+fn clean_pure<F: lexiclean::Flavor>(
+  root: Option<&Path>,
+  path: &Path,
+) -> Result<(PathBuf, bool), Error> {
+  let path = path.to_str().ok_or(Error::Unicode)?;
+
+  let root = root
+    .map(|root| root.to_str().map(PurePath::<F>::new).ok_or(Error::Unicode))
+    .transpose()?;
+
+  let cleaned = match &root {
+    Some(root) => root.join(path).lexiclean(),
+    None => PurePath::<F>::new(path).lexiclean(),
+  };
+
+  let contained = root.map_or(true, |root| cleaned.is_within(&root));
+
+  Ok((cleaned.into_string().into(), contained))
+}
+
+fn write_path(stdout: &mut impl Write, path: &Path, delimiter: u8) -> io::Result<()> {
+  stdout.write_all(path.as_os_str().as_encoded_bytes())?;
+  stdout.write_all(&[delimiter])
+}
+
+/// Strip the `\r` of `\r\n` line endings on Windows.
+fn strip_carriage_return<'a>(arguments: &Arguments, path: &'a Path) -> &'a Path {
+  if cfg!(windows) && !arguments.null {
+    if let Some(path) = path.to_str().and_then(|path| path.strip_suffix('\r')) {
+      return Path::new(path);
+    }
+  }
+  path
+}
+
+fn main() {
+  let stdin = io::stdin();
+  let stdout = io::stdout();
+
+  match run(
+    env::args_os().skip(1),
+    stdin.lock(),
+    stdout.lock(),
+    stdin.is_terminal(),
+  ) {
+    Ok(true) => {}
+    Ok(false) => process::exit(1),
+    Err(Error::Io(error)) if error.kind() == io::ErrorKind::BrokenPipe => {}
+    Err(error) => {
+      eprintln!("error: {}", error);
+      process::exit(2);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[track_caller]
+  fn case(args: &[&str], stdin: &str, want: &str) {
+    check(args, stdin, want, true);
+  }
+
+  #[track_caller]
+  fn check(args: &[&str], stdin: &str, want: &str, contained: bool) {
+    let mut stdout = Vec::new();
+    assert_eq!(
+      run(
+        args.iter().map(OsString::from),
+        stdin.as_bytes(),
+        &mut stdout,
+        false,
+      )
+      .unwrap(),
+      contained
+    );
+    assert_eq!(String::from_utf8(stdout).unwrap(), want);
+  }
+
+  #[test]
+  fn arguments_are_cleaned() {
+    case(
+      &["foo/./bar", "/../baz/", ""],
+      "ignored\n",
+      "foo/bar\n/baz\n.\n",
+    );
+  }
+
+  #[test]
+  fn stdin_is_cleaned() {
+    case(&[], "foo/../bar\n\na//b/\n", "bar\n.\na/b\n");
+    case(&[], "foo/..", ".\n");
+    case(&[], "", "");
+  }
+
+  #[test]
+  fn paths_are_separated_by_nul() {
+    case(&["-0"], "a/../b\0c\nd/.\0e\r\0", "b\0c\nd\0e\r\0");
+    case(&["--null", "foo/.", "bar"], "", "foo\0bar\0");
+    check(
+      &["-0", "--check", "--root", "/srv"],
+      "a\n/../b\0../c\0",
+      "../c\0",
+      false,
+    );
+  }
+
+  #[test]
+  fn interactive_output_is_flushed_after_each_path() {
+    struct Writes(Vec<Vec<u8>>);
+
+    impl Write for Writes {
+      fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.push(buf.into());
+        Ok(buf.len())
+      }
+
+      fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+      }
+    }
+
+    let mut writes = Writes(Vec::new());
+    run(Vec::new(), &b"a/..\nb/\n"[..], &mut writes, true).unwrap();
+    assert_eq!(writes.0, [b".\n".to_vec(), b"b\n".to_vec()]);
+
+    let mut writes = Writes(Vec::new());
+    run(Vec::new(), &b"a/..\nb/\n"[..], &mut writes, false).unwrap();
+    assert_eq!(writes.0, [b".\nb\n".to_vec()]);
+  }
+
+  #[test]
+  fn options_are_parsed() {
+    case(&["--", "-foo/..", "--help"], "", ".\n--help\n");
+    case(&["-"], "", "-\n");
+
+    let mut stdout = Vec::new();
+    run(vec!["-h".into()], &b""[..], &mut stdout, false).unwrap();
+    assert!(String::from_utf8(stdout).unwrap().starts_with("Usage:"));
+
+    assert!(matches!(
+      Arguments::parse(vec!["--bogus".into()]),
+      Err(Error::Usage(message)) if message == "unknown option `--bogus`"
+    ));
+  }
+
+  #[test]
+  fn paths_are_joined_onto_root() {
+    case(
+      &["--root", "/srv", "www/./a", "../b"],
+      "",
+      "/srv/www/a\n/b\n",
+    );
+    case(&["--root=srv/", "/etc"], "", "/etc\n");
+  }
+
+  #[test]
+  fn flavor_is_selected() {
+    case(&["--posix", r"a\b/../c"], "", "c\n");
+    case(&["--windows", r"a\b/../c"], "", "a\\c\n");
+    case(&["--windows", "--windows", "C:/x/.."], "", "C:\\\n");
+    case(
+      &["--windows", "--root", r"C:\srv", r"..\x", r"\y"],
+      "",
+      "C:\\x\nC:\\y\n",
+    );
+    check(
+      &["--windows", "--check", "--root", r"C:\srv"],
+      "www\n..\\etc\nD:\\srv\n",
+      "..\\etc\nD:\\srv\n",
+      false,
+    );
+    check(
+      &["--windows", "--check", "--root", r"c:\srv"],
+      "C:\\srv\\x\n",
+      "",
+      true,
+    );
+
+    assert!(matches!(
+      Arguments::parse(vec!["--posix".into(), "--windows".into()]),
+      Err(Error::Usage(message)) if message == "`--posix` and `--windows` cannot be combined"
+    ));
+  }
+
+  #[test]
+  fn paths_outside_of_root_are_listed() {
+    check(
+      &["--check", "--root", "/srv/www"],
+      "index.html\n../etc/passwd\na/../../www/b\n/srv/www/c\n/etc\n",
+      "../etc/passwd\n/etc\n",
+      false,
+    );
+    check(&["--check", "--root", "srv", "a", "a/.."], "", "", true);
+
+    assert!(matches!(
+      Arguments::parse(vec!["--check".into()]),
+      Err(Error::Usage(message)) if message == "`--check` requires `--root`"
+    ));
+    assert!(matches!(
+      Arguments::parse(vec!["--root".into()]),
+      Err(Error::Usage(message)) if message == "`--root` requires a value"
+    ));
+  }
+}
@@ -0,0 +1,58 @@
+use {
+  crate::Lexiclean,
+  camino::{Utf8Path, Utf8PathBuf},
+  std::path::PathBuf,
+};
+
+/// Cleaning only removes components and inserts separators, `.`, and `..`,
+/// so the cleaned form of a UTF-8 path is always UTF-8.
+fn from_cleaned(path: PathBuf) -> Utf8PathBuf {
+  Utf8PathBuf::from_path_buf(path)
+    .unwrap_or_else(|path| unreachable!("cleaned path is not UTF-8: {}", path.display()))
+}
+
+impl Lexiclean for &Utf8Path {
+  type Output = Utf8PathBuf;
+
+  fn lexiclean(self) -> Utf8PathBuf {
+    from_cleaned(self.as_std_path().lexiclean())
+  }
+}
+
+impl Lexiclean for &Utf8PathBuf {
+  type Output = Utf8PathBuf;
+
+  fn lexiclean(self) -> Utf8PathBuf {
+    self.as_path().lexiclean()
+  }
+}
+
+impl Lexiclean for Utf8PathBuf {
+  type Output = Utf8PathBuf;
+
+  fn lexiclean(self) -> Utf8PathBuf {
+    from_cleaned(self.into_std_path_buf().lexiclean())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn utf8_paths_are_cleaned() {
+    assert_eq!(Utf8Path::new("foo/./bar/..").lexiclean(), "foo");
+    assert_eq!(Utf8Path::new("").lexiclean(), ".");
+    assert_eq!(Utf8PathBuf::from("/../ü//é/").lexiclean(), "/ü/é");
+    assert_eq!((&Utf8PathBuf::from("a/../../b")).lexiclean(), "../b");
+  }
+
+  #[test]
+  fn other_lexiclean_methods_are_available() {
+    assert!(Utf8Path::new("a/b/../c").is_within("a"));
+    assert_eq!(
+      Utf8Path::new("/..").lexiclean_strict(),
+      Err(crate::Error::Escape)
+    );
+  }
+}
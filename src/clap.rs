@@ -0,0 +1,210 @@
+use {
+  crate::{AbsolutePathBuf, CleanPathBuf, Error, RelativePathBuf},
+  ::clap::{
+    builder::{TypedValueParser, ValueParserFactory},
+    error::ErrorKind,
+    Arg, Command,
+  },
+  std::{convert::TryFrom, ffi::OsStr, marker::PhantomData, path::Path},
+};
+
+/// Value parser for `CleanPathBuf`, `RelativePathBuf`, and `AbsolutePathBuf`
+/// arguments, available with the `clap` feature, and used by
+/// `clap::value_parser!` for those types. Arguments are cleaned and
+/// validated like `T::try_from`, and like `PathBuf` arguments, may not be
+/// empty.
+///
+/// ```
+/// use {
+///   clap::{value_parser, Arg, Command},
+///   lexiclean::RelativePathBuf,
+/// };
+///
+/// let command = Command::new("serve")
+///   .arg(Arg::new("dir").value_parser(value_parser!(RelativePathBuf)));
+///
+/// let matches = command.clone().get_matches_from(["serve", "public/./img/"]);
+/// assert_eq!(
+///   matches.get_one::<RelativePathBuf>("dir"),
+///   Some(&RelativePathBuf::new("public/img").unwrap()),
+/// );
+///
+/// assert!(command.try_get_matches_from(["serve", "/etc"]).is_err());
+/// ```
+pub struct CleanPathValueParser<T> {
+  marker: PhantomData<fn() -> T>,
+}
+
+impl<T> CleanPathValueParser<T> {
+  pub fn new() -> Self {
+    Self {
+      marker: PhantomData,
+    }
+  }
+}
+
+impl<T> Clone for CleanPathValueParser<T> {
+  fn clone(&self) -> Self {
+    Self::new()
+  }
+}
+
+impl<T> Default for CleanPathValueParser<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> TypedValueParser for CleanPathValueParser<T>
+where
+  T: for<'a> TryFrom<&'a Path, Error = Error> + Clone + Send + Sync + 'static,
+{
+  type Value = T;
+
+  fn parse_ref(&self, cmd: &Command, arg: Option<&Arg>, value: &OsStr) -> Result<T, ::clap::Error> {
+    let arg = arg.map_or_else(|| "...".to_owned(), ToString::to_string);
+
+    if value.is_empty() {
+      return Err(cmd.clone().error(
+        ErrorKind::InvalidValue,
+        format!("a value is required for '{}' but none was supplied", arg),
+      ));
+    }
+
+    T::try_from(Path::new(value)).map_err(|error| {
+      cmd.clone().error(
+        ErrorKind::ValueValidation,
+        format!(
+          "invalid value '{}' for '{}': {}",
+          value.to_string_lossy(),
+          arg,
+          error,
+        ),
+      )
+    })
+  }
+}
+
+impl ValueParserFactory for CleanPathBuf {
+  type Parser = CleanPathValueParser<Self>;
+
+  fn value_parser() -> Self::Parser {
+    CleanPathValueParser::new()
+  }
+}
+
+impl ValueParserFactory for RelativePathBuf {
+  type Parser = CleanPathValueParser<Self>;
+
+  fn value_parser() -> Self::Parser {
+    CleanPathValueParser::new()
+  }
+}
+
+impl ValueParserFactory for AbsolutePathBuf {
+  type Parser = CleanPathValueParser<Self>;
+
+  fn value_parser() -> Self::Parser {
+    CleanPathValueParser::new()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    ::clap::{value_parser, ArgMatches},
+  };
+
+  fn parse<T>(value: &str) -> Result<ArgMatches, ::clap::Error>
+  where
+    T: ValueParserFactory + Clone + Send + Sync + 'static,
+    T::Parser: TypedValueParser<Value = T>,
+  {
+    Command::new("test")
+      .arg(Arg::new("path").value_parser(value_parser!(T)))
+      .try_get_matches_from(["test", value])
+  }
+
+  #[test]
+  fn arguments_are_cleaned() {
+    let matches = parse::<CleanPathBuf>("foo/./bar/../baz/").unwrap();
+    assert_eq!(
+      matches.get_one::<CleanPathBuf>("path").unwrap(),
+      &CleanPathBuf::new("foo/baz"),
+    );
+
+    let matches = parse::<RelativePathBuf>("a/../../b").unwrap();
+    assert_eq!(
+      matches.get_one::<RelativePathBuf>("path").unwrap(),
+      &RelativePathBuf::new("../b").unwrap(),
+    );
+
+    let root = if cfg!(windows) {
+      r"C:\srv\.\www"
+    } else {
+      "/srv/./www"
+    };
+    let matches = parse::<AbsolutePathBuf>(root).unwrap();
+    assert_eq!(
+      matches.get_one::<AbsolutePathBuf>("path").unwrap(),
+      &AbsolutePathBuf::new(root).unwrap(),
+    );
+  }
+
+  #[test]
+  fn invalid_arguments_are_rejected() {
+    #[track_caller]
+    fn case(error: ::clap::Error, kind: ErrorKind, message: &str) {
+      assert_eq!(error.kind(), kind);
+      assert!(
+        error.to_string().starts_with(message),
+        "{:?} does not start with {:?}",
+        error.to_string(),
+        message,
+      );
+    }
+
+    let absolute = if cfg!(windows) { r"C:\etc" } else { "/etc" };
+
+    case(
+      parse::<RelativePathBuf>(absolute).unwrap_err(),
+      ErrorKind::ValueValidation,
+      &format!(
+        "error: invalid value '{}' for '[path]': path is absolute",
+        absolute
+      ),
+    );
+
+    case(
+      parse::<AbsolutePathBuf>("etc").unwrap_err(),
+      ErrorKind::ValueValidation,
+      "error: invalid value 'etc' for '[path]': path is relative",
+    );
+
+    case(
+      parse::<CleanPathBuf>("foo\0bar").unwrap_err(),
+      ErrorKind::ValueValidation,
+      "error: invalid value 'foo\0bar' for '[path]': path contains a NUL byte",
+    );
+
+    case(
+      parse::<CleanPathBuf>("").unwrap_err(),
+      ErrorKind::InvalidValue,
+      "error: a value is required for '[path]' but none was supplied",
+    );
+
+    case(
+      Command::new("test")
+        .arg(
+          Arg::new("root")
+            .long("root")
+            .value_parser(value_parser!(AbsolutePathBuf)),
+        )
+        .try_get_matches_from(["test", "--root", "srv"])
+        .unwrap_err(),
+      ErrorKind::ValueValidation,
+      "error: invalid value 'srv' for '--root <root>': path is relative",
+    );
+  }
+}
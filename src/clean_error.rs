@@ -0,0 +1,172 @@
+use {
+  crate::Error,
+  std::{
+    ffi::{OsStr, OsString},
+    fmt::{self, Display, Formatter},
+    path::Path,
+  },
+};
+
+/// Error returned by `Lexicleaner::try_clean_detailed`, which names the
+/// component that caused cleaning to fail, so applications can point users
+/// at exactly where a path escaped or was invalid.
+///
+/// ```
+/// use lexiclean::{Error, Lexicleaner};
+///
+/// let error = Lexicleaner::new()
+///   .deny_escape(true)
+///   .try_clean_detailed("/srv/../..")
+///   .unwrap_err();
+///
+/// assert_eq!(error.error(), Error::Escape);
+/// assert_eq!(error.component(), "..");
+/// assert_eq!(error.index(), 3);
+/// assert_eq!(error.offset(), 8);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CleanError {
+  component: OsString,
+  error: Error,
+  index: usize,
+  offset: usize,
+}
+
+impl CleanError {
+  /// The error for the component of `path` at `index` in `Path::components`.
+  pub(crate) fn new(path: &Path, index: usize, error: Error) -> Self {
+    let mut components = path.components();
+
+    for _ in 0..index {
+      components.next();
+    }
+
+    // The remaining path starts at the next component, since leading
+    // separators and `.` components are skipped.
+    let offset = components.as_path().as_os_str().as_encoded_bytes().as_ptr() as usize
+      - path.as_os_str().as_encoded_bytes().as_ptr() as usize;
+
+    Self {
+      component: components
+        .next()
+        .map(|component| component.as_os_str().to_owned())
+        .unwrap_or_default(),
+      error,
+      index,
+      offset,
+    }
+  }
+
+  /// The offending component.
+  pub fn component(&self) -> &OsStr {
+    &self.component
+  }
+
+  pub fn error(&self) -> Error {
+    self.error
+  }
+
+  /// The index of the offending component in `Path::components`.
+  pub fn index(&self) -> usize {
+    self.index
+  }
+
+  /// The byte offset of the offending component in the encoded bytes of the
+  /// original path, as returned by `OsStr::as_encoded_bytes`.
+  pub fn offset(&self) -> usize {
+    self.offset
+  }
+}
+
+impl Display for CleanError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{}: `{}`, component {} at byte {}",
+      self.error,
+      Path::new(&self.component).display(),
+      self.index,
+      self.offset
+    )
+  }
+}
+
+impl std::error::Error for CleanError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    Some(&self.error)
+  }
+}
+
+impl From<CleanError> for Error {
+  fn from(error: CleanError) -> Self {
+    error.error
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::Lexicleaner};
+
+  #[track_caller]
+  fn case(cleaner: Lexicleaner, path: &str, error: Error, component: &str, index: usize) {
+    let err = cleaner.try_clean_detailed(path).unwrap_err();
+    assert_eq!(err.error(), error, "{}", path);
+    assert_eq!(err.component(), component, "{}", path);
+    assert_eq!(err.index(), index, "{}", path);
+    assert_eq!(
+      &path.as_bytes()[err.offset()..err.offset() + component.len()],
+      component.as_bytes(),
+      "{}",
+      path
+    );
+  }
+
+  #[test]
+  fn offending_components_are_located() {
+    let cleaner = Lexicleaner::new()
+      .deny_escape(true)
+      .deny_control_characters(true)
+      .deny_reserved_names(true);
+
+    case(cleaner, "/..", Error::Escape, "..", 1);
+    case(cleaner, "//foo//.././../bar", Error::Escape, "..", 3);
+    case(
+      cleaner,
+      "./foo/./nul.txt",
+      Error::ReservedName,
+      "nul.txt",
+      2,
+    );
+    case(cleaner, "foo\n/bar", Error::ControlCharacter, "foo\n", 0);
+    case(
+      Lexicleaner::new().max_components(2),
+      "a/b/c",
+      Error::TooManyComponents { max: 2 },
+      "c",
+      2,
+    );
+  }
+
+  #[test]
+  fn clean_paths_succeed() {
+    assert_eq!(
+      Lexicleaner::new()
+        .deny_escape(true)
+        .try_clean_detailed("/foo/./bar/.."),
+      Ok("/foo".into())
+    );
+  }
+
+  #[test]
+  fn errors_are_displayed() {
+    let error = Lexicleaner::new()
+      .deny_escape(true)
+      .try_clean_detailed("/foo/../..")
+      .unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "path ascends above its root: `..`, component 3 at byte 8"
+    );
+    assert_eq!(Error::from(error), Error::Escape);
+  }
+}
@@ -0,0 +1,289 @@
+use {
+  crate::{check_nul, clean_join, is_verbatim, Error, Lexiclean},
+  std::{
+    borrow::Borrow,
+    convert::TryFrom,
+    ffi::OsStr,
+    mem,
+    ops::Deref,
+    path::{self, Component, Path, PathBuf},
+    str::FromStr,
+  },
+};
+
+/// An owned path which is always clean.
+///
+/// Construction cleans the path, and methods which modify it keep it clean,
+/// so a `CleanPathBuf` can be passed to code which requires a clean path
+/// without cleaning it again. `CleanPathBuf` dereferences to `Path`.
+///
+/// ```
+/// use lexiclean::CleanPathBuf;
+/// use std::path::Path;
+///
+/// let mut path = CleanPathBuf::new("foo/./bar");
+/// assert_eq!(path, Path::new("foo/bar"));
+///
+/// path.push("../baz");
+/// assert_eq!(path, Path::new("foo/baz"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CleanPathBuf {
+  path: PathBuf,
+}
+
+impl CleanPathBuf {
+  pub fn new(path: impl AsRef<Path>) -> Self {
+    Self {
+      path: path.as_ref().lexiclean(),
+    }
+  }
+
+  pub fn as_path(&self) -> &Path {
+    &self.path
+  }
+
+  pub fn into_path_buf(self) -> PathBuf {
+    self.path
+  }
+
+  /// Join `path` onto `self`, like `push`, returning a new `CleanPathBuf`.
+  pub fn join(&self, path: impl AsRef<Path>) -> Self {
+    let mut joined = self.clone();
+    joined.push(path);
+    joined
+  }
+
+  /// Push `path` onto `self`, keeping it clean. Relative paths are pushed
+  /// one component at a time, so only the components of `path` are
+  /// examined, and each `..` pops a component from `self`. As with
+  /// `PathBuf::push`, an absolute `path` replaces `self`. If `self` has a
+  /// Windows verbatim prefix, `..` is an ordinary name, so components are
+  /// appended without popping.
+  pub fn push(&mut self, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+
+    if let Some(Component::Prefix(_)) | Some(Component::RootDir) = path.components().next() {
+      self.path = clean_join(&self.path, path);
+      return;
+    }
+
+    if is_verbatim(&self.path) {
+      for component in path.components() {
+        if component == Component::CurDir {
+          continue;
+        }
+
+        let mut verbatim = mem::take(&mut self.path).into_os_string();
+        if !verbatim.as_encoded_bytes().ends_with(b"\\") {
+          verbatim.push("\\");
+        }
+        verbatim.push(component);
+        self.path = verbatim.into();
+      }
+      return;
+    }
+
+    for component in path.components() {
+      match component {
+        Component::Prefix(_) | Component::RootDir | Component::CurDir => {}
+        Component::ParentDir => match self.path.components().next_back() {
+          Some(Component::Normal(_)) => {
+            self.path.pop();
+            if self.path.as_os_str().is_empty() {
+              self.path.push(Component::CurDir);
+            }
+          }
+          Some(Component::RootDir) => {}
+          Some(Component::CurDir) => self.path = Component::ParentDir.as_os_str().into(),
+          _ => self.path.push(component),
+        },
+        Component::Normal(name) => {
+          if self.path.components().next_back() == Some(Component::CurDir) {
+            self.path = name.into();
+          } else {
+            self.path.push(name);
+          }
+        }
+      }
+    }
+  }
+
+  /// Replace or remove the extension of the final component, like
+  /// `PathBuf::set_extension`. Returns `false` and does nothing if the path
+  /// has no file name, for example if it is `..`, or if `extension` contains
+  /// a separator, which would make the path unclean, and for which
+  /// `PathBuf::set_extension` panics.
+  pub fn set_extension(&mut self, extension: impl AsRef<OsStr>) -> bool {
+    let extension = extension.as_ref();
+
+    if extension
+      .as_encoded_bytes()
+      .iter()
+      .any(|&byte| path::is_separator(byte.into()))
+    {
+      return false;
+    }
+
+    self.path.set_extension(extension)
+  }
+}
+
+impl Deref for CleanPathBuf {
+  type Target = Path;
+
+  fn deref(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl AsRef<Path> for CleanPathBuf {
+  fn as_ref(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl Borrow<Path> for CleanPathBuf {
+  fn borrow(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl From<CleanPathBuf> for PathBuf {
+  fn from(path: CleanPathBuf) -> Self {
+    path.path
+  }
+}
+
+/// Parsing fails with `Error::Nul` if the path contains a NUL byte.
+impl FromStr for CleanPathBuf {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Error> {
+    Self::try_from(Path::new(s))
+  }
+}
+
+/// Conversion fails with `Error::Nul` if the path contains a NUL byte.
+impl TryFrom<&Path> for CleanPathBuf {
+  type Error = Error;
+
+  fn try_from(path: &Path) -> Result<Self, Error> {
+    check_nul(path)?;
+    Ok(Self::new(path))
+  }
+}
+
+impl PartialEq<Path> for CleanPathBuf {
+  fn eq(&self, other: &Path) -> bool {
+    self.path == other
+  }
+}
+
+impl PartialEq<&Path> for CleanPathBuf {
+  fn eq(&self, other: &&Path) -> bool {
+    self.path == *other
+  }
+}
+
+impl PartialEq<PathBuf> for CleanPathBuf {
+  fn eq(&self, other: &PathBuf) -> bool {
+    self.path == *other
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[track_caller]
+  fn push(base: &str, path: &str, want: &str) {
+    let mut clean = CleanPathBuf::new(base);
+    clean.push(path);
+    assert_eq!(clean, Path::new(want), "{} {}", base, path);
+    assert_eq!(
+      clean.as_os_str(),
+      Path::new(base).join(path).lexiclean().as_os_str(),
+      "{} {}",
+      base,
+      path
+    );
+  }
+
+  #[test]
+  fn construction_cleans() {
+    assert_eq!(CleanPathBuf::new("foo/./bar/"), Path::new("foo/bar"));
+    assert_eq!(CleanPathBuf::new("").as_os_str(), ".");
+    assert_eq!(CleanPathBuf::new("/..").as_os_str(), "/");
+  }
+
+  #[test]
+  fn paths_are_parsed() {
+    assert_eq!(
+      "foo/./bar".parse::<CleanPathBuf>().unwrap(),
+      Path::new("foo/bar")
+    );
+    assert_eq!("foo\0bar".parse::<CleanPathBuf>(), Err(Error::Nul));
+    assert_eq!(
+      CleanPathBuf::try_from(Path::new("foo/.."))
+        .unwrap()
+        .as_os_str(),
+      "."
+    );
+  }
+
+  #[test]
+  fn push_keeps_path_clean() {
+    push("foo", "bar", "foo/bar");
+    push("foo", "./bar/", "foo/bar");
+    push("foo", "..", ".");
+    push("foo", "../..", "..");
+    push("foo/bar", "../baz", "foo/baz");
+    push(".", "foo", "foo");
+    push(".", "..", "..");
+    push(".", ".", ".");
+    push("..", "..", "../..");
+    push("..", "foo/..", "..");
+    push("/", "..", "/");
+    push("/foo", "../..", "/");
+    push("foo", "/bar/../baz", "/baz");
+    push("foo", "", "foo");
+  }
+
+  #[test]
+  fn join_keeps_path_clean() {
+    let base = CleanPathBuf::new("foo");
+    assert_eq!(base.join("../bar"), Path::new("bar"));
+    assert_eq!(base, Path::new("foo"));
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn parent_dirs_are_appended_to_verbatim_paths() {
+    let mut path = CleanPathBuf::new(r"\\?\C:\foo");
+    path.push(r"..\.\bar/baz");
+    assert_eq!(path.as_os_str(), r"\\?\C:\foo\..\bar\baz");
+
+    let mut path = CleanPathBuf::new(r"\\?\C:\");
+    path.push("..");
+    assert_eq!(path.as_os_str(), r"\\?\C:\..");
+  }
+
+  #[test]
+  fn set_extension_keeps_path_clean() {
+    let mut path = CleanPathBuf::new("foo/bar.txt");
+    assert!(path.set_extension("md"));
+    assert_eq!(path, Path::new("foo/bar.md"));
+
+    assert!(path.set_extension(""));
+    assert_eq!(path, Path::new("foo/bar"));
+
+    let mut path = CleanPathBuf::new("..");
+    assert!(!path.set_extension("md"));
+    assert_eq!(path, Path::new(".."));
+
+    let mut path = CleanPathBuf::new("foo/bar");
+    assert!(!path.set_extension("a/.."));
+    assert_eq!(path, Path::new("foo/bar"));
+  }
+}
@@ -0,0 +1,1016 @@
+use {
+  crate::{
+    pure_path::{is_separator, parse_prefix, separator, PrefixKind},
+    win32, Flavor, PurePath,
+  },
+  std::path::Path,
+};
+
+/// A path cleaning function of another language or library, whose output
+/// `PurePath::lexiclean_compat` reproduces exactly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compat {
+  /// Go's `path/filepath.Clean`. `""` is cleaned to `.`, `..` components
+  /// directly following the root are removed, and on Windows, a bare drive
+  /// like `C:` is cleaned to `C:.`, and a relative path whose first
+  /// component contains a `:` is prefixed with `.\`, so that it is not
+  /// mistaken for a drive.
+  Go,
+  /// CPython 3.12's `os.path.normpath`, from `posixpath` or `ntpath`. On
+  /// POSIX, exactly two leading slashes are preserved. On Windows, `/` is
+  /// replaced with `\`, and paths starting with `\\.\` or `\\?\` are
+  /// returned unchanged.
+  Python,
+  /// Node.js 22's `path.posix.normalize` or `path.win32.normalize`. A
+  /// trailing separator is preserved, `""` is cleaned to `.`, and on Windows,
+  /// a bare drive like `C:` is cleaned to `C:.`, `\\server\share` is given
+  /// a trailing separator, and `\\.\` and `\\?\` are device roots, so
+  /// `\\?\foo` is not given one.
+  Node,
+  /// C++17's `std::filesystem::path::lexically_normal`. `""` is cleaned to
+  /// `""`, a trailing separator is preserved, and added when a trailing `.`
+  /// or `..` is removed, so `foo/bar/..` is cleaned to `foo/`. On Windows,
+  /// the root name of a UNC path is only `\\server`, so the share can be
+  /// removed by `..`.
+  Cpp,
+  /// Java's `java.nio.file.Path.normalize`. A path with no components
+  /// remaining is cleaned to `""`, not `.`, so `.` and `foo/..` are both
+  /// cleaned to `""`, leading `..` components of a relative path are kept,
+  /// and a trailing separator is removed. On Windows, `/` is replaced with
+  /// `\`, and a UNC root like `\\server\share` is given a trailing
+  /// separator.
+  Java,
+  /// Win32's `PathCchCanonicalizeEx`, with `PATHCCH_ALLOW_LONG_PATHS`. Only
+  /// `\` is a separator, and prefixes are always parsed as on Windows,
+  /// regardless of the flavor. `..` components are clamped at the root, `""`
+  /// is cleaned to `\`, trailing dots are removed, `\\?\` is removed from
+  /// disk and UNC paths, and added to disk paths that are too long to be used
+  /// without it.
+  PathCch,
+}
+
+impl<F: Flavor> PurePath<F> {
+  /// Clean the path exactly like `compat`, using the flavor's separators and
+  /// prefixes.
+  ///
+  /// ```
+  /// use lexiclean::{Compat, PurePosixPath, PureWindowsPath};
+  ///
+  /// assert_eq!(PurePosixPath::new("").lexiclean_compat(Compat::Go).as_str(), ".");
+  /// assert_eq!(PureWindowsPath::new("C:").lexiclean_compat(Compat::Go).as_str(), "C:.");
+  /// ```
+  pub fn lexiclean_compat(&self, compat: Compat) -> Self {
+    Self::new(match compat {
+      Compat::Go => go::<F>(self.as_str()),
+      Compat::Python => python::<F>(self.as_str()),
+      Compat::Node => node::<F>(self.as_str()),
+      Compat::Cpp => cpp::<F>(self.as_str()),
+      Compat::Java => java::<F>(self.as_str()),
+      Compat::PathCch => path_cch(self.as_str()),
+    })
+  }
+}
+
+/// Elements of `path`, with `..` removed along with the preceding element,
+/// and `.` and empty elements removed. If `rooted`, `..` elements which
+/// would ascend above the root are removed, otherwise they are kept.
+fn resolve(path: &str, rooted: bool, separator: fn(char) -> bool) -> Vec<&str> {
+  let mut elements = Vec::new();
+
+  for element in path.split(separator) {
+    match element {
+      "" | "." => {}
+      ".." => match elements.last() {
+        Some(&last) if last != ".." => {
+          elements.pop();
+        }
+        None if rooted => {}
+        _ => elements.push(element),
+      },
+      _ => elements.push(element),
+    }
+  }
+
+  elements
+}
+
+fn go<F: Flavor>(path: &str) -> String {
+  let separator = separator::<F>();
+
+  let (volume, kind, rest) = match go_volume::<F>(path) {
+    Some((volume, kind)) => (volume, Some(kind), &path[volume.len()..]),
+    None => ("", None, path),
+  };
+
+  if rest.is_empty() && kind.is_some() && kind != Some(PrefixKind::Disk) {
+    return volume.replace('/', r"\");
+  }
+
+  let rooted = rest.starts_with(is_separator::<F>);
+
+  let elements = resolve(rest, rooted, is_separator::<F>);
+
+  let mut out = volume.replace('/', r"\");
+
+  if rooted {
+    out.push(separator);
+  }
+
+  for (i, element) in elements.iter().enumerate() {
+    if i > 0 {
+      out.push(separator);
+    }
+    out.push_str(element);
+  }
+
+  if elements.is_empty() && !rooted {
+    out.push('.');
+  }
+
+  // Go only guards paths which cleaning changed, so that relative paths like
+  // `a/../c:` are not cleaned into drives, or into `\??\` paths.
+  if F::WINDOWS && kind.is_none() && !path.starts_with(&out) {
+    if out
+      .split(separator)
+      .next()
+      .unwrap_or_default()
+      .contains(':')
+    {
+      out.insert_str(0, r".\");
+    } else if out.starts_with(r"\??\") {
+      out.insert_str(0, r"\.");
+    }
+  }
+
+  out
+}
+
+/// Go's volume name of `path`, which, unlike `parse_prefix`, includes
+/// `\??\` prefixes.
+fn go_volume<F: Flavor>(path: &str) -> Option<(&str, PrefixKind)> {
+  if F::WINDOWS {
+    if let Some(rest) = path.strip_prefix(r"\??\") {
+      let len = rest.find(is_separator::<F>).unwrap_or(rest.len());
+      return Some((&path[..4 + len], PrefixKind::Verbatim));
+    }
+  }
+
+  parse_prefix::<F>(path)
+}
+
+fn python<F: Flavor>(path: &str) -> String {
+  if !F::WINDOWS {
+    let slashes = if path.starts_with("//") && !path.starts_with("///") {
+      2
+    } else if path.starts_with('/') {
+      1
+    } else {
+      0
+    };
+
+    let mut out = "/".repeat(slashes);
+    out.push_str(&resolve(path, slashes > 0, |c| c == '/').join("/"));
+
+    if out.is_empty() {
+      out.push('.');
+    }
+
+    return out;
+  }
+
+  if path.starts_with(r"\\.\") || path.starts_with(r"\\?\") {
+    return path.into();
+  }
+
+  let path = path.replace('/', r"\");
+
+  let (prefix, rooted, tail) = python_split_root(&path);
+
+  let mut out = prefix.to_owned();
+  out.push_str(&resolve(tail, rooted, |c| c == '\\').join(r"\"));
+
+  if out.is_empty() {
+    out.push('.');
+  }
+
+  out
+}
+
+/// Split `path`, in which separators have been replaced with `\`, into its
+/// drive and root, whether it has a root, and the rest of the path, like
+/// `ntpath.splitroot`.
+fn python_split_root(path: &str) -> (&str, bool, &str) {
+  let bytes = path.as_bytes();
+
+  if let Some(rest) = path.strip_prefix(r"\\") {
+    let server = match rest.find('\\') {
+      Some(server) => 2 + server,
+      None => return (path, false, ""),
+    };
+
+    match path[server + 1..].find('\\') {
+      Some(share) => {
+        let share = server + 1 + share;
+        (&path[..share + 1], true, &path[share + 1..])
+      }
+      None => (path, false, ""),
+    }
+  } else if let Some(rest) = path.strip_prefix('\\') {
+    (&path[..1], true, rest)
+  } else if bytes.get(1) == Some(&b':') {
+    let drive = path.chars().next().unwrap().len_utf8() + 1;
+    if path[drive..].starts_with('\\') {
+      (&path[..drive + 1], true, &path[drive + 1..])
+    } else {
+      (&path[..drive], false, &path[drive..])
+    }
+  } else {
+    ("", false, path)
+  }
+}
+
+fn node<F: Flavor>(path: &str) -> String {
+  if path.is_empty() {
+    return ".".into();
+  }
+
+  let trailing = path.ends_with(is_separator::<F>);
+
+  if !F::WINDOWS {
+    let rooted = path.starts_with('/');
+
+    let mut out = if rooted { "/" } else { "" }.to_owned();
+    out.push_str(&resolve(path, rooted, |c| c == '/').join("/"));
+
+    if out.is_empty() {
+      out.push('.');
+    }
+
+    if trailing && !out.ends_with('/') {
+      out.push('/');
+    }
+
+    return out;
+  }
+
+  if path.len() == 1 {
+    return if path == "/" {
+      r"\".into()
+    } else {
+      path.into()
+    };
+  }
+
+  let separator = |c: char| c == '/' || c == '\\';
+
+  let mut device = None;
+  let mut rooted = false;
+  let mut rest = path;
+
+  if let Some(unc) = path.strip_prefix(separator) {
+    rooted = true;
+    rest = unc;
+
+    if let Some(unc) = unc.strip_prefix(separator) {
+      rest = unc;
+
+      let server = unc.find(separator).unwrap_or(unc.len());
+
+      if server > 0 && server < unc.len() && matches!(&unc[..server], "." | "?") {
+        // `\\.\` and `\\?\` are device roots, and are never UNC roots.
+        device = Some(format!(r"\\{}", &unc[..server]));
+        rest = &unc[server..];
+      } else if server > 0 && server < unc.len() {
+        let after_server = unc[server..].trim_start_matches(separator);
+
+        if !after_server.is_empty() {
+          let share = after_server.find(separator).unwrap_or(after_server.len());
+
+          if share == after_server.len() {
+            return format!(r"\\{}\{}\", &unc[..server], after_server);
+          }
+
+          device = Some(format!(r"\\{}\{}", &unc[..server], &after_server[..share]));
+          rest = &after_server[share..];
+        }
+      }
+    }
+  } else if path.as_bytes()[0].is_ascii_alphabetic() && path.as_bytes()[1] == b':' {
+    device = Some(path[..2].to_owned());
+    rest = &path[2..];
+    if let Some(tail) = rest.strip_prefix(separator) {
+      rooted = true;
+      rest = tail;
+    }
+  }
+
+  let mut tail = resolve(rest, rooted, separator).join(r"\");
+
+  if tail.is_empty() && !rooted {
+    tail.push('.');
+  }
+
+  if !tail.is_empty() && trailing {
+    tail.push('\\');
+  }
+
+  // Guard against relative paths which cleaning turns into drives, from the
+  // fix for CVE-2024-36139.
+  if !rooted && device.is_none() && path.contains(':') {
+    let bytes = tail.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+      return format!(r".\{}", tail);
+    }
+
+    for (i, _) in path.match_indices(':') {
+      if path[i + 1..].is_empty() || path[i + 1..].starts_with(separator) {
+        return format!(r".\{}", tail);
+      }
+    }
+  }
+
+  match (device, rooted) {
+    (None, false) => tail,
+    (None, true) => format!(r"\{}", tail),
+    (Some(device), false) => format!("{}{}", device, tail),
+    (Some(device), true) => format!(r"{}\{}", device, tail),
+  }
+}
+
+fn cpp<F: Flavor>(path: &str) -> String {
+  if path.is_empty() {
+    return String::new();
+  }
+
+  let separator = separator::<F>();
+
+  let root_name = if F::WINDOWS { cpp_root_name(path) } else { 0 };
+
+  let (root_name, rest) = path.split_at(root_name);
+
+  let rooted = rest.starts_with(is_separator::<F>);
+
+  let mut elements = Vec::new();
+  let mut trailing = false;
+
+  for element in rest
+    .split(is_separator::<F>)
+    .filter(|element| !element.is_empty())
+  {
+    trailing = false;
+    match element {
+      "." => trailing = true,
+      ".." => match elements.last() {
+        Some(&last) if last != ".." => {
+          elements.pop();
+          trailing = true;
+        }
+        None if rooted => {}
+        _ => elements.push(element),
+      },
+      _ => elements.push(element),
+    }
+  }
+
+  let trailing = trailing || rest.ends_with(is_separator::<F>);
+
+  let mut out = root_name.replace(is_separator::<F>, &separator.to_string());
+
+  if rooted {
+    out.push(separator);
+  }
+
+  for (i, element) in elements.iter().enumerate() {
+    if i > 0 {
+      out.push(separator);
+    }
+    out.push_str(element);
+  }
+
+  if trailing && elements.last().is_some_and(|&last| last != "..") {
+    out.push(separator);
+  }
+
+  if out.is_empty() {
+    out.push('.');
+  }
+
+  out
+}
+
+/// The length of the root name of a Windows path, as determined by MSVC's
+/// `std::filesystem`: `\\?`, `\\.`, or `\??` for paths starting with
+/// `\\?\`, `\\.\`, or `\??\`, a drive like `C:`, or `\\server`.
+fn cpp_root_name(path: &str) -> usize {
+  let separator = |byte: Option<&u8>| matches!(byte, Some(b'/' | b'\\'));
+
+  let bytes = path.as_bytes();
+
+  if separator(bytes.first()) && separator(bytes.get(3)) {
+    match (bytes.get(1), bytes.get(2)) {
+      (Some(b'/' | b'\\'), Some(b'?' | b'.')) | (Some(b'?'), Some(b'?')) => return 3,
+      _ => {}
+    }
+  }
+
+  if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+    return 2;
+  }
+
+  if separator(bytes.first())
+    && separator(bytes.get(1))
+    && !separator(bytes.get(2))
+    && bytes.len() > 2
+  {
+    return 2 + path[2..].find(['/', '\\']).unwrap_or(path.len() - 2);
+  }
+
+  0
+}
+
+fn java<F: Flavor>(path: &str) -> String {
+  if !F::WINDOWS {
+    let rooted = path.starts_with('/');
+    let mut out = if rooted { "/" } else { "" }.to_owned();
+    out.push_str(&resolve(path, rooted, |c| c == '/').join("/"));
+    return out;
+  }
+
+  let path = path.replace('/', r"\");
+
+  let (prefix, rooted, tail) = python_split_root(&path);
+
+  let mut out = prefix.to_owned();
+
+  if !rooted && prefix.starts_with(r"\\") && prefix[2..].contains('\\') {
+    out.push('\\');
+  }
+
+  out.push_str(&resolve(tail, rooted, |c| c == '\\').join(r"\"));
+
+  out
+}
+
+/// A port of Wine's implementation of `PathCchCanonicalizeEx`, which
+/// matches Windows, including its quirks.
+fn path_cch(path: &str) -> String {
+  let src = path.as_bytes();
+  let mut buffer = Vec::with_capacity(path.len() + 5);
+  let mut i = 0;
+  let mut root_end = path_cch_root_end(src);
+
+  if let Some(end) = root_end {
+    let root = &src[..(end + 1).min(src.len())];
+    i = root.len();
+
+    if let Some(rest) = root.strip_prefix(br"\\?\UNC\") {
+      buffer.extend_from_slice(br"\\");
+      buffer.extend_from_slice(rest);
+      root_end = Some(buffer.len());
+    } else if is_prefixed_disk(root) {
+      buffer.extend_from_slice(&root[4..]);
+      if buffer.len() == 2 {
+        buffer.push(b'\\');
+      }
+      root_end = Some(buffer.len());
+    } else {
+      buffer.extend_from_slice(root);
+    }
+  }
+
+  let removable = |buffer: &[u8], root_end: Option<usize>| {
+    buffer.last() == Some(&b'\\') && root_end.map_or(true, |end| buffer.len() - 1 > end)
+  };
+
+  while i < src.len() {
+    if src[i] != b'.' {
+      buffer.push(src[i]);
+      i += 1;
+      continue;
+    }
+
+    let after_separator = buffer.last().map_or(true, |&c| c == b'\\');
+
+    if src.get(i + 1) == Some(&b'.') {
+      if buffer.last() == Some(&b'*') {
+        buffer.push(b'.');
+        i += 1;
+        continue;
+      }
+
+      let next = src.get(i + 2).copied();
+
+      if next.is_some_and(|c| c != b'\\') || !after_separator {
+        buffer.extend_from_slice(b"..");
+        i += 2;
+        continue;
+      }
+
+      if removable(&buffer, root_end) {
+        buffer.pop();
+        while let Some(c) = buffer.pop() {
+          if c == b'\\' {
+            break;
+          }
+        }
+      } else if next == Some(b'\\') {
+        i += 1;
+      }
+
+      i += 2;
+    } else {
+      let next = src.get(i + 1).copied();
+
+      if next.is_some_and(|c| c != b'\\') || !after_separator {
+        buffer.push(b'.');
+        i += 1;
+        continue;
+      }
+
+      if removable(&buffer, root_end) {
+        buffer.pop();
+      } else if next == Some(b'\\') {
+        i += 1;
+      }
+
+      i += 1;
+    }
+
+    if is_drive_spec(&buffer) && buffer.get(2) != Some(&b'\\') {
+      buffer.truncate(2);
+      buffer.push(b'\\');
+      root_end = Some(2);
+      if src.get(i) == Some(&b'\\') {
+        i += 1;
+      }
+    }
+  }
+
+  while buffer.last() == Some(&b'.') {
+    let len = buffer.len();
+    if len > 1 && buffer[len - 2] == b'*' {
+      break;
+    } else if len == 3 && buffer[1] == b':' {
+      buffer[2] = b'\\';
+      break;
+    } else {
+      buffer.pop();
+    }
+  }
+
+  if buffer.is_empty() {
+    buffer.push(b'\\');
+  }
+
+  // Bytes are only removed up to, and replaced with, ASCII characters.
+  let out = String::from_utf8(buffer).unwrap();
+
+  if is_drive_spec(out.as_bytes()) && win32::is_long(Path::new(&out)) {
+    format!(r"\\?\{}", out)
+  } else {
+    out
+  }
+}
+
+/// The index of the last byte of the root of `path`, like Wine's
+/// `get_root_end`.
+fn path_cch_root_end(path: &[u8]) -> Option<usize> {
+  let separator_at = |i: usize| path.get(i) == Some(&b'\\');
+
+  if path.len() >= 48 && path[..11].eq_ignore_ascii_case(br"\\?\Volume{") && path[47] == b'}' {
+    Some(if separator_at(48) { 48 } else { 47 })
+  } else if path.starts_with(br"\\?\UNC\") {
+    Some(7)
+  } else if is_prefixed_disk(path) {
+    Some(if separator_at(6) { 6 } else { 5 })
+  } else if path.starts_with(br"\\") {
+    Some(1)
+  } else if path.starts_with(br"\") {
+    Some(0)
+  } else if is_drive_spec(path) {
+    Some(if separator_at(2) { 2 } else { 1 })
+  } else {
+    None
+  }
+}
+
+fn is_drive_spec(path: &[u8]) -> bool {
+  path.len() >= 2 && path[0].is_ascii_alphabetic() && path[1] == b':'
+}
+
+fn is_prefixed_disk(path: &[u8]) -> bool {
+  path.starts_with(br"\\?\") && is_drive_spec(&path[4..])
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::{PurePosixPath, PureWindowsPath},
+  };
+
+  #[track_caller]
+  fn case(compat: Compat, posix: &[(&str, &str)], windows: &[(&str, &str)]) {
+    for (path, want) in posix {
+      assert_eq!(
+        PurePosixPath::new(*path).lexiclean_compat(compat).as_str(),
+        *want,
+        "{}",
+        path
+      );
+    }
+
+    for (path, want) in windows {
+      assert_eq!(
+        PureWindowsPath::new(*path)
+          .lexiclean_compat(compat)
+          .as_str(),
+        *want,
+        "{}",
+        path
+      );
+    }
+  }
+
+  // Cases from Go's `path/filepath` tests.
+  #[test]
+  fn go() {
+    case(
+      Compat::Go,
+      &[
+        ("", "."),
+        ("abc", "abc"),
+        ("abc/def", "abc/def"),
+        ("a/b/c", "a/b/c"),
+        (".", "."),
+        ("..", ".."),
+        ("../..", "../.."),
+        ("../../abc", "../../abc"),
+        ("/abc", "/abc"),
+        ("/", "/"),
+        ("abc/", "abc"),
+        ("abc/def/", "abc/def"),
+        ("/abc/", "/abc"),
+        ("//abc", "/abc"),
+        ("///abc", "/abc"),
+        ("abc//def//ghi", "abc/def/ghi"),
+        ("abc/./def", "abc/def"),
+        ("/./abc/def", "/abc/def"),
+        ("abc/.", "abc"),
+        ("abc/def/ghi/../jkl", "abc/def/jkl"),
+        ("abc/def/../ghi/../jkl", "abc/jkl"),
+        ("abc/def/..", "abc"),
+        ("abc/def/../..", "."),
+        ("/abc/def/../..", "/"),
+        ("abc/def/../../..", ".."),
+        ("/abc/def/../../..", "/"),
+        ("abc/def/../../../ghi/jkl/../../../mno", "../../mno"),
+        ("/../abc", "/abc"),
+        ("abc/./../def", "def"),
+        ("abc//./../def", "def"),
+        ("abc/../../././../def", "../../def"),
+      ],
+      &[
+        (r"c:", r"c:."),
+        (r"c:\", r"c:\"),
+        (r"c:\abc", r"c:\abc"),
+        (r"c:abc\..\..\.\.\..\def", r"c:..\..\def"),
+        (r"c:\abc\def\..\..", r"c:\"),
+        (r"c:\..\abc", r"c:\abc"),
+        (r"c:..\abc", r"c:..\abc"),
+        (r"c:\b:\..\..\..\d", r"c:\d"),
+        (r"\", r"\"),
+        (r"/", r"\"),
+        (r"\\i\..\c$", r"\\i\..\c$"),
+        (r"\\i\..\i\c$", r"\\i\..\i\c$"),
+        (r"\\i\..\I\c$", r"\\i\..\I\c$"),
+        (r"\\host\share\foo\..\bar", r"\\host\share\bar"),
+        (r"//host/share/foo/../baz", r"\\host\share\baz"),
+        (r"\\host\share\foo\..\..\..\..\bar", r"\\host\share\bar"),
+        (r"\\.\C:\a\..\..\..\..\bar", r"\\.\C:\bar"),
+        (r"\\.\C:\\\\a", r"\\.\C:\a"),
+        (r"\\a\b\..\c", r"\\a\b\c"),
+        (r"\\a\b", r"\\a\b"),
+        (r".\c:", r".\c:"),
+        (r".\c:\foo", r".\c:\foo"),
+        (r".\c:foo", r".\c:foo"),
+        (r"//abc", r"\\abc"),
+        (r"///abc", r"\\\abc"),
+        (r"//abc//", r"\\abc\\"),
+        (r"a/../c:", r".\c:"),
+        (r"a\..\c:", r".\c:"),
+        (r"a/../c:/a", r".\c:\a"),
+        (r"a/../../c:", r"..\c:"),
+        (r"foo:bar", r"foo:bar"),
+        (r"foo:bar\.", r"foo:bar"),
+        (r"x\..\foo:bar", r".\foo:bar"),
+        (r"\a\..\??\c:\b", r"\.\??\c:\b"),
+        (r"\??\c:\a\..\b", r"\??\c:\b"),
+      ],
+    );
+  }
+
+  // Cases from CPython's `posixpath` and `ntpath` tests.
+  #[test]
+  fn python() {
+    case(
+      Compat::Python,
+      &[
+        ("", "."),
+        ("/", "/"),
+        ("//", "//"),
+        ("///", "/"),
+        ("///foo/.//bar//", "/foo/bar"),
+        ("///foo/.//bar//.//..//.//baz///", "/foo/baz"),
+        ("///..//./foo/.//bar", "/foo/bar"),
+        ("//foo/../bar", "//bar"),
+        (".", "."),
+        (".//.", "."),
+        ("foo/..", "."),
+        ("foo/../..", ".."),
+        ("../foo", "../foo"),
+        ("/..", "/"),
+        (r"foo\..", r"foo\.."),
+      ],
+      &[
+        ("A//////././//.//B", r"A\B"),
+        ("A/./B", r"A\B"),
+        ("A/foo/../B", r"A\B"),
+        ("C:A//B", r"C:A\B"),
+        ("D:A/./B", r"D:A\B"),
+        ("e:A/foo/../B", r"e:A\B"),
+        ("C:///A//B", r"C:\A\B"),
+        ("D:///A/./B", r"D:\A\B"),
+        ("e:///A/foo/../B", r"e:\A\B"),
+        ("..", ".."),
+        (".", "."),
+        ("", "."),
+        ("C:", "C:"),
+        ("C:foo/..", "C:"),
+        ("/", r"\"),
+        ("c:/", r"c:\"),
+        ("/../.././..", r"\"),
+        ("c:/../../..", r"c:\"),
+        ("../.././..", r"..\..\.."),
+        ("K:../.././..", r"K:..\..\.."),
+        ("C:////a/b", r"C:\a\b"),
+        ("//machine/share//a/b", r"\\machine\share\a\b"),
+        (r"\\.\NUL", r"\\.\NUL"),
+        (r"\\?\D:/XY\Z", r"\\?\D:/XY\Z"),
+        ("handbook/../../Tests/image.png", r"..\Tests\image.png"),
+        (
+          "handbook/../../../Tests/image.png",
+          r"..\..\Tests\image.png",
+        ),
+        ("handbook///../a/.././../b/c", r"..\b\c"),
+        ("handbook/a/../..///../../b/c", r"..\..\b\c"),
+        ("//server/share/..", r"\\server\share\"),
+        ("//server/share/../", r"\\server\share\"),
+        ("//server/share/../..", r"\\server\share\"),
+        ("//server/share/../../", r"\\server\share\"),
+        ("//server/share", r"\\server\share"),
+        ("//server", r"\\server"),
+      ],
+    );
+  }
+
+  // Cases from Node.js's `path.normalize` tests.
+  #[test]
+  fn node() {
+    case(
+      Compat::Node,
+      &[
+        ("", "."),
+        ("./", "./"),
+        ("/", "/"),
+        ("./fixtures///b/../b/c.js", "fixtures/b/c.js"),
+        ("/foo/../../../bar", "/bar"),
+        ("a//b//../b", "a/b"),
+        ("a//b//./c", "a/b/c"),
+        ("a//b//.", "a/b"),
+        ("/a/b/c/../../../x/y/z", "/x/y/z"),
+        ("///..//./foo/.//bar", "/foo/bar"),
+        ("bar/foo../../", "bar/"),
+        ("bar/foo../..", "bar"),
+        ("bar/foo../../baz", "bar/baz"),
+        ("bar/foo../", "bar/foo../"),
+        ("bar/foo..", "bar/foo.."),
+        ("../foo../../../bar", "../../bar"),
+        ("../.../.././.../../../bar", "../../bar"),
+        ("../../../foo/../../../bar", "../../../../../bar"),
+        ("../../../foo/../../../bar/../../", "../../../../../../"),
+        ("../foobar/barfoo/foo/../../../bar/../../", "../../"),
+        ("../.../../foobar/../../../bar/../../baz", "../../../../baz"),
+        (r"foo/bar\baz", r"foo/bar\baz"),
+        ("foo/..", "."),
+        ("foo/../", "./"),
+      ],
+      &[
+        ("", "."),
+        ("/", r"\"),
+        (".", "."),
+        ("./fixtures///b/../b/c.js", r"fixtures\b\c.js"),
+        ("/foo/../../../bar", r"\bar"),
+        ("a//b//../b", r"a\b"),
+        ("a//b//./c", r"a\b\c"),
+        ("a//b//.", r"a\b"),
+        (
+          "//server/share/dir/file.ext",
+          r"\\server\share\dir\file.ext",
+        ),
+        ("//server/share", r"\\server\share\"),
+        ("//?/b", r"\\?\b"),
+        (r"\\./c:/.", r"\\.\c:"),
+        (r"\\./.\sh", r"\\.\sh"),
+        (r"\\.\pipe\..\..\foo\", r"\\.\foo\"),
+        (r"\\?\", r"\\?\"),
+        ("/a/b/c/../../../x/y/z", r"\x\y\z"),
+        ("C:", "C:."),
+        (r"C:..\abc", r"C:..\abc"),
+        (r"C:..\..\abc\..\def", r"C:..\..\def"),
+        (r"C:\.", r"C:\"),
+        ("file:stream", "file:stream"),
+        (r"bar\foo..\..\", r"bar\"),
+        (r"bar\foo..\..", "bar"),
+        (r"bar\foo..\..\baz", r"bar\baz"),
+        (r"bar\foo..\", r"bar\foo..\"),
+        (r"bar\foo..", r"bar\foo.."),
+        (r"..\foo..\..\..\bar", r"..\..\bar"),
+        (r"..\...\..\.\...\..\..\bar", r"..\..\bar"),
+        ("../../../foo/../../../bar", r"..\..\..\..\..\bar"),
+        ("../../../foo/../../../bar/../../", r"..\..\..\..\..\..\"),
+        ("../foobar/barfoo/foo/../../../bar/../../", r"..\..\"),
+        (
+          "../.../../foobar/../../../bar/../../baz",
+          r"..\..\..\..\baz",
+        ),
+        (r"foo/bar\baz", r"foo\bar\baz"),
+        ("test/../C:/Windows", r".\C:\Windows"),
+        ("test/../C:Windows", r".\C:Windows"),
+        ("./upload/../C:/Windows", r".\C:\Windows"),
+        ("test/some/../../path/../C:/Windows", r".\C:\Windows"),
+        ("test/some/../../path/../C:Windows", r".\C:Windows"),
+      ],
+    );
+  }
+
+  // Cases from the C++ standard and libstdc++'s `lexically_normal` tests.
+  #[test]
+  fn cpp() {
+    case(
+      Compat::Cpp,
+      &[
+        ("", ""),
+        (".", "."),
+        ("./", "."),
+        ("..", ".."),
+        ("../", ".."),
+        ("/", "/"),
+        ("//", "/"),
+        ("/..", "/"),
+        ("/../", "/"),
+        ("/../..", "/"),
+        ("foo/./bar/..", "foo/"),
+        ("foo/.///bar/../", "foo/"),
+        ("foo/bar/", "foo/bar/"),
+        ("foo/.", "foo/"),
+        ("foo/..", "."),
+        ("foo/../", "."),
+        ("foo/../..", ".."),
+        ("../foo/..", ".."),
+        ("../foo/../", ".."),
+        ("foo/../bar", "bar"),
+        ("/foo/../..", "/"),
+        ("/foo/../../bar", "/bar"),
+        ("a/b/../../c/./d/", "c/d/"),
+        ("//a//b", "/a/b"),
+        ("..//../.", "../.."),
+      ],
+      &[
+        ("", ""),
+        ("C:", "C:"),
+        ("C:foo/..", "C:"),
+        ("C:foo/../..", "C:.."),
+        ("C:/foo/..", r"C:\"),
+        ("C:/..", r"C:\"),
+        (r"C:\foo\.\bar\", r"C:\foo\bar\"),
+        (r"foo/bar\..", r"foo\"),
+        (r"//server/share/..", r"\\server\"),
+        (r"\\server\share\foo", r"\\server\share\foo"),
+        (r"\\server", r"\\server"),
+        (r"\\?\C:\foo\..", r"\\?\C:\"),
+        (r"\\.\pipe\..\..", r"\\.\"),
+        (r"\??\C:\foo\..\bar", r"\??\C:\bar"),
+      ],
+    );
+  }
+
+  // Cases from OpenJDK's `PathOps` tests.
+  #[test]
+  fn java() {
+    case(
+      Compat::Java,
+      &[
+        ("", ""),
+        (".", ""),
+        ("./", ""),
+        ("..", ".."),
+        ("/", "/"),
+        ("//", "/"),
+        ("/..", "/"),
+        ("/../..", "/"),
+        ("/foo", "/foo"),
+        ("/foo/", "/foo"),
+        ("//foo//bar", "/foo/bar"),
+        ("foo/.", "foo"),
+        ("foo/..", ""),
+        ("foo/../..", ".."),
+        ("../foo/..", ".."),
+        ("../../foo", "../../foo"),
+        ("foo/./bar/../baz", "foo/baz"),
+        ("/foo/../../bar", "/bar"),
+        ("/./foo/.", "/foo"),
+      ],
+      &[
+        ("", ""),
+        (".", ""),
+        ("C:", "C:"),
+        ("C:.", "C:"),
+        ("C:..", "C:.."),
+        ("C:foo\\..", "C:"),
+        (r"C:\", r"C:\"),
+        (r"C:\..", r"C:\"),
+        (r"C:\foo\..\bar\", r"C:\bar"),
+        (r"C:/foo/./bar", r"C:\foo\bar"),
+        (r"\foo\..\..", r"\"),
+        (r"foo\bar\..\..\..", r".."),
+        (r"\\server\share", r"\\server\share\"),
+        (r"//server/share/foo/..", r"\\server\share\"),
+        (r"\\server\share\..\foo", r"\\server\share\foo"),
+      ],
+    );
+  }
+
+  // Cases from the `PathCchCanonicalize` documentation and Wine's tests.
+  #[test]
+  fn path_cch() {
+    let cases = &[
+      (r"A:\name_1\.\name_2\..\name_3", r"A:\name_1\name_3"),
+      (r"A:\name_1\..\name_2\.\name_3", r"A:\name_2\name_3"),
+      (
+        r"A:\name_1\name_2\.\name_3\..\name_4",
+        r"A:\name_1\name_2\name_4",
+      ),
+      (
+        r"A:\name_1\.\name_2\.\name_3\..\name_4\..",
+        r"A:\name_1\name_2",
+      ),
+      ("", r"\"),
+      (r"\", r"\"),
+      (r"\.", r"\"),
+      (r"\..", r"\"),
+      (r"\a\..", r"\"),
+      (".", r"\"),
+      ("..", r"\"),
+      (r"..\a", "a"),
+      (r"a\.", "a"),
+      (r"a\..", r"\"),
+      (r"a\.\b", r"a\b"),
+      ("a.", "a"),
+      ("a...", "a"),
+      ("*.", "*."),
+      ("*..", "*."),
+      ("a.b", "a.b"),
+      ("...", r"\"),
+      ("a/./b", "a/./b"),
+      ("C:", "C:"),
+      ("C:.", r"C:\"),
+      ("C:..", r"C:\"),
+      (r"C:\", r"C:\"),
+      (r"C:\.", r"C:\"),
+      (r"C:\..", r"C:\"),
+      (r"C:\..\..\a", r"C:\a"),
+      (r"C:\a\..", r"C:\"),
+      (r"C:\a\.\", r"C:\a\"),
+      (r"\\?\C:", r"C:\"),
+      (r"\\?\C:\a\..\b", r"C:\b"),
+      (r"\\?\UNC\server\share", r"\\server\share"),
+      (r"\\server\share\..", r"\\server"),
+      (r"\\server\share\.\a", r"\\server\share\a"),
+      (
+        r"\\?\Volume{00000000-0000-0000-0000-000000000000}\a\.\b\..",
+        r"\\?\Volume{00000000-0000-0000-0000-000000000000}\a",
+      ),
+    ];
+
+    case(Compat::PathCch, cases, cases);
+
+    let long = format!(r"C:\{}", "a".repeat(win32::MAX_PATH));
+    assert_eq!(
+      PureWindowsPath::new(&*format!(r"{}\b\..", long))
+        .lexiclean_compat(Compat::PathCch)
+        .as_str(),
+      format!(r"\\?\{}", long),
+    );
+  }
+}
@@ -0,0 +1,157 @@
+use crate::{lexiclean_posix, lexiclean_windows, PureComponent, PureWindowsPath};
+
+/// Convert a Windows path to a Cygwin path, cleaning it in the process, so
+/// `C:\foo\..\bar` becomes `/cygdrive/c/bar`, and `\\server\share\foo`
+/// becomes `//server/share/foo`. Relative paths are converted by replacing
+/// separators.
+///
+/// Returns `None` for paths that Cygwin cannot name without knowing the
+/// current drive or directory, like `C:foo` and `\foo`, and for verbatim and
+/// device paths.
+///
+/// ```
+/// assert_eq!(lexiclean::to_cygwin(r"C:\foo\..\bar").unwrap(), "/cygdrive/c/bar");
+/// ```
+pub fn to_cygwin(path: &str) -> Option<String> {
+  windows_to_mount(path, "/cygdrive", true)
+}
+
+/// Like `to_cygwin`, but with the drive mount points used by MSYS, so
+/// `C:\foo` becomes `/c/foo`.
+pub fn to_msys(path: &str) -> Option<String> {
+  windows_to_mount(path, "", true)
+}
+
+/// Convert a Cygwin or MSYS path to a Windows path, cleaning it in the
+/// process, so `/cygdrive/c/foo/../bar` and `/c/foo/../bar` both become
+/// `C:\bar`, and `//server/share/foo` becomes `\\server\share\foo`. Relative
+/// paths are converted by replacing separators.
+///
+/// Returns `None` for other absolute paths, like `/usr/bin`, whose Windows
+/// path depends on where Cygwin or MSYS is installed.
+///
+/// ```
+/// assert_eq!(lexiclean::from_cygwin("/cygdrive/c/foo").unwrap(), r"C:\foo");
+/// assert_eq!(lexiclean::from_cygwin("/c/foo").unwrap(), r"C:\foo");
+/// ```
+pub fn from_cygwin(path: &str) -> Option<String> {
+  if path.starts_with("//") && !path.starts_with("///") {
+    return Some(lexiclean_windows(&path.replace('/', r"\")));
+  }
+
+  mount_to_windows(path, "/cygdrive").or_else(|| mount_to_windows(path, ""))
+}
+
+/// Convert a Windows path to a path in which drives are mounted at
+/// `root/<letter>`. UNC paths are converted to `//server/share` if `unc` is
+/// `true`.
+pub(crate) fn windows_to_mount(path: &str, root: &str, unc: bool) -> Option<String> {
+  use PureComponent::*;
+
+  let cleaned = PureWindowsPath::new(path).lexiclean();
+  let mut components = cleaned.components().peekable();
+
+  let mut out = match components.peek() {
+    Some(Prefix(prefix)) => {
+      let prefix = prefix.as_bytes();
+      let out = match prefix {
+        [letter, b':'] => format!("{}/{}", root, char::from(letter.to_ascii_lowercase())),
+        [b'\\', b'\\', b'.' | b'?', b'\\', ..] => return None,
+        [b'\\', b'\\', ..] if unc => prefix
+          .iter()
+          .map(|&byte| if byte == b'\\' { '/' } else { byte.into() })
+          .collect(),
+        _ => return None,
+      };
+      components.next();
+      match components.next() {
+        Some(RootDir) => {}
+        None if !matches!(prefix, [_, b':']) => {}
+        _ => return None,
+      }
+      out
+    }
+    Some(RootDir) => return None,
+    _ => String::new(),
+  };
+
+  for component in components {
+    if !out.is_empty() {
+      out.push('/');
+    }
+
+    out.push_str(match component {
+      Normal(name) => name,
+      CurDir => ".",
+      ParentDir => "..",
+      Prefix(_) | RootDir => unreachable!(),
+    });
+  }
+
+  Some(out)
+}
+
+/// Convert a path in which drives are mounted at `root/<letter>` to a
+/// Windows path. Returns `None` for absolute paths not under `root`.
+pub(crate) fn mount_to_windows(path: &str, root: &str) -> Option<String> {
+  let cleaned = lexiclean_posix(path);
+
+  let rest = match cleaned.strip_prefix('/') {
+    Some(_) => cleaned.strip_prefix(root)?.strip_prefix('/')?,
+    None => return Some(cleaned.replace('/', r"\")),
+  };
+
+  let (drive, rest) = rest.split_once('/').unwrap_or((rest, ""));
+
+  match drive.as_bytes() {
+    [letter] if letter.is_ascii_alphabetic() => Some(format!(
+      r"{}:\{}",
+      char::from(letter.to_ascii_uppercase()),
+      rest.replace('/', r"\"),
+    )),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn windows_paths_are_converted_to_cygwin() {
+    assert_eq!(to_cygwin(r"C:\foo\bar").unwrap(), "/cygdrive/c/foo/bar");
+    assert_eq!(to_cygwin(r"c:/foo/./bar/..").unwrap(), "/cygdrive/c/foo");
+    assert_eq!(to_cygwin(r"D:\").unwrap(), "/cygdrive/d");
+    assert_eq!(
+      to_cygwin(r"\\server\share\foo").unwrap(),
+      "//server/share/foo"
+    );
+    assert_eq!(to_cygwin(r"\\server\share").unwrap(), "//server/share");
+    assert_eq!(to_cygwin(r"foo\..\..\bar").unwrap(), "../bar");
+    assert_eq!(to_cygwin(r"foo\..").unwrap(), ".");
+    assert_eq!(to_msys(r"C:\foo\bar").unwrap(), "/c/foo/bar");
+  }
+
+  #[test]
+  fn ambiguous_windows_paths_are_not_converted() {
+    for path in [r"C:foo", r"C:", r"\foo", r"\\?\C:\foo", r"\\.\pipe\foo"] {
+      assert_eq!(to_cygwin(path), None, "{}", path);
+    }
+  }
+
+  #[test]
+  fn cygwin_paths_are_converted_to_windows() {
+    assert_eq!(from_cygwin("/cygdrive/c/foo/bar").unwrap(), r"C:\foo\bar");
+    assert_eq!(from_cygwin("/cygdrive/c").unwrap(), r"C:\");
+    assert_eq!(from_cygwin("/c/foo//./bar/").unwrap(), r"C:\foo\bar");
+    assert_eq!(from_cygwin("/d").unwrap(), r"D:\");
+    assert_eq!(
+      from_cygwin("//server/share/foo/..").unwrap(),
+      r"\\server\share\"
+    );
+    assert_eq!(from_cygwin("foo/../../bar").unwrap(), r"..\bar");
+    assert_eq!(from_cygwin("/usr/bin"), None);
+    assert_eq!(from_cygwin("/cygdrive"), None);
+    assert_eq!(from_cygwin("/"), None);
+  }
+}
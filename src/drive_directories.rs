@@ -0,0 +1,146 @@
+use {
+  crate::{
+    pure_path::{parse_prefix, PrefixKind},
+    PureWindowsPath, Windows,
+  },
+  alloc::{collections::BTreeMap, format, string::String},
+};
+
+/// The current directory, and the current directories of other drives, for
+/// resolving Windows paths to absolute paths lexically, like
+/// `GetFullPathName`, without being on Windows. Paths are parsed as Windows
+/// paths on every platform, and resolved paths use `\` as their separator.
+///
+/// Windows keeps a current directory for each drive, against which
+/// drive-relative paths like `C:foo` are resolved. The current directory of
+/// the drive of the current directory is the current directory itself, and a
+/// drive without a current directory is resolved against its root.
+///
+/// ```
+/// use lexiclean::DriveDirectories;
+///
+/// let directories = DriveDirectories::new(r"C:\Users\user").drive('D', r"D:\src");
+///
+/// assert_eq!(directories.resolve(r"foo\..\bar"), r"C:\Users\user\bar");
+/// assert_eq!(directories.resolve(r"D:lexiclean"), r"D:\src\lexiclean");
+/// assert_eq!(directories.resolve(r"\Windows"), r"C:\Windows");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DriveDirectories {
+  current_dir: PureWindowsPath,
+  drives: BTreeMap<char, PureWindowsPath>,
+}
+
+impl DriveDirectories {
+  pub fn new(current_dir: impl AsRef<str>) -> Self {
+    Self {
+      current_dir: PureWindowsPath::new(current_dir.as_ref()).lexiclean(),
+      drives: BTreeMap::new(),
+    }
+  }
+
+  /// Set the current directory of drive `letter`, which is matched
+  /// case-insensitively.
+  pub fn drive(mut self, letter: char, dir: impl AsRef<str>) -> Self {
+    self.drives.insert(
+      letter.to_ascii_uppercase(),
+      PureWindowsPath::new(dir.as_ref()).lexiclean(),
+    );
+    self
+  }
+
+  /// The cleaned current directory.
+  pub fn current_dir(&self) -> &str {
+    self.current_dir.as_str()
+  }
+
+  /// Resolve `path` to an absolute path, and clean it. Drive-relative paths,
+  /// like `C:foo`, are resolved against the current directory of their
+  /// drive, paths with a root but no prefix, like `\foo`, against the drive
+  /// of the current directory, and relative paths against the current
+  /// directory.
+  pub fn resolve(&self, path: impl AsRef<str>) -> String {
+    let path = path.as_ref();
+
+    if let Some((prefix, PrefixKind::Disk)) = parse_prefix::<Windows>(path) {
+      let rest = &path[prefix.len()..];
+      if !rest.starts_with(['\\', '/']) {
+        return self
+          .drive_dir(prefix.as_bytes()[0].into())
+          .join(rest)
+          .lexiclean()
+          .into_string();
+      }
+    }
+
+    self.current_dir.join(path).lexiclean().into_string()
+  }
+
+  fn drive_dir(&self, letter: char) -> PureWindowsPath {
+    let letter = letter.to_ascii_uppercase();
+
+    if let Some(dir) = self.drives.get(&letter) {
+      return dir.clone();
+    }
+
+    if let Some((prefix, PrefixKind::Disk)) = parse_prefix::<Windows>(self.current_dir.as_str()) {
+      if prefix.as_bytes()[0].eq_ignore_ascii_case(&(letter as u8)) {
+        return self.current_dir.clone();
+      }
+    }
+
+    PureWindowsPath::new(format!(r"{}:\", letter))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn relative_paths_are_resolved_against_current_dir() {
+    let directories = DriveDirectories::new(r"C:\foo\.\bar");
+    assert_eq!(directories.current_dir(), r"C:\foo\bar");
+    assert_eq!(directories.resolve("baz"), r"C:\foo\bar\baz");
+    assert_eq!(directories.resolve("../baz"), r"C:\foo\baz");
+    assert_eq!(directories.resolve(""), r"C:\foo\bar");
+    assert_eq!(directories.resolve(r"C:\baz\.."), r"C:\");
+  }
+
+  #[test]
+  fn drive_relative_paths_are_resolved_against_drive_dir() {
+    let directories = DriveDirectories::new(r"C:\foo")
+      .drive('d', r"D:\bar")
+      .drive('E', r"E:\baz\.");
+
+    assert_eq!(directories.resolve(r"C:qux"), r"C:\foo\qux");
+    assert_eq!(directories.resolve(r"c:qux"), r"C:\foo\qux");
+    assert_eq!(directories.resolve(r"D:qux"), r"D:\bar\qux");
+    assert_eq!(directories.resolve(r"e:..\qux"), r"E:\qux");
+    assert_eq!(directories.resolve(r"F:qux"), r"F:\qux");
+    assert_eq!(directories.resolve(r"f:qux"), r"F:\qux");
+    assert_eq!(directories.resolve(r"D:"), r"D:\bar");
+    assert_eq!(directories.resolve(r"D:\qux"), r"D:\qux");
+    assert_eq!(directories.resolve(r"D:/qux/."), r"D:\qux");
+  }
+
+  #[test]
+  fn rooted_paths_are_resolved_against_current_drive() {
+    let directories = DriveDirectories::new(r"C:\foo").drive('D', r"D:\bar");
+    assert_eq!(directories.resolve(r"\qux"), r"C:\qux");
+    assert_eq!(directories.resolve("/qux"), r"C:\qux");
+    assert_eq!(directories.resolve(r"qux"), r"C:\foo\qux");
+    assert_eq!(
+      directories.resolve(r"\\server\share\qux"),
+      r"\\server\share\qux",
+    );
+  }
+
+  #[test]
+  fn current_dir_may_be_unc() {
+    let directories = DriveDirectories::new(r"\\server\share\foo");
+    assert_eq!(directories.resolve("bar"), r"\\server\share\foo\bar");
+    assert_eq!(directories.resolve(r"\bar"), r"\\server\share\bar");
+    assert_eq!(directories.resolve(r"C:bar"), r"C:\bar");
+  }
+}
@@ -0,0 +1,16 @@
+/// Case to normalize Windows drive letters to, with
+/// `Lexicleaner::drive_letter_case`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DriveLetterCase {
+  Lower,
+  Upper,
+}
+
+impl DriveLetterCase {
+  pub(crate) fn apply(self, letter: u8) -> char {
+    match self {
+      Self::Lower => letter.to_ascii_lowercase().into(),
+      Self::Upper => letter.to_ascii_uppercase().into(),
+    }
+  }
+}
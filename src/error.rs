@@ -0,0 +1,46 @@
+use core::fmt::{self, Display, Formatter};
+
+/// Error returned by fallible cleaning.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+  /// The path is absolute, but must be relative.
+  Absolute,
+  /// A component contains a C0 or C1 control character, like a newline.
+  ControlCharacter,
+  /// The path has invalid, double, or disallowed percent-encoding.
+  Encoding,
+  /// A `..` component would ascend above the root of the path.
+  Escape,
+  /// The path contains a NUL byte, which operating systems do not allow.
+  Nul,
+  /// The path is relative, but must be absolute.
+  Relative,
+  /// A component is a Windows reserved device name, like `NUL`.
+  ReservedName,
+  /// A component has alternate data stream syntax, like `file:stream`.
+  Stream,
+  /// The path has more than `max` components.
+  TooManyComponents { max: usize },
+  /// The path is not valid Unicode.
+  Unicode,
+}
+
+impl Display for Error {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Absolute => write!(f, "path is absolute"),
+      Self::ControlCharacter => write!(f, "path contains a control character"),
+      Self::Encoding => write!(f, "path has invalid percent-encoding"),
+      Self::Escape => write!(f, "path ascends above its root"),
+      Self::Nul => write!(f, "path contains a NUL byte"),
+      Self::Relative => write!(f, "path is relative"),
+      Self::ReservedName => write!(f, "path has a reserved device name"),
+      Self::Stream => write!(f, "path has an alternate data stream"),
+      Self::TooManyComponents { max } => write!(f, "path has more than {} components", max),
+      Self::Unicode => write!(f, "path is not valid Unicode"),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
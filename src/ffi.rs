@@ -0,0 +1,140 @@
+//! C functions for cleaning NUL-terminated POSIX paths, declared in
+//! `include/lexiclean.h`. Paths are cleaned like `lexiclean_to_buf`, which
+//! matches `Lexiclean::lexiclean` on Unix.
+
+use {
+  crate::lexiclean_to_buf,
+  alloc::{ffi::CString, vec},
+  core::{
+    ffi::{c_char, CStr},
+    ptr, slice,
+  },
+};
+
+/// Clean the NUL-terminated path `input` into `out`, which is `out_len`
+/// bytes long, and NUL-terminate it. Returns the length of the cleaned path,
+/// not including the NUL, or -1 if `input` or `out` is null, or if `out` is
+/// too small. A buffer of `strlen(input) + 2` bytes is always large enough.
+///
+/// # Safety
+///
+/// `input` must be null or point to a NUL-terminated string, and `out` must
+/// be null or point to `out_len` writable bytes, which may not overlap
+/// `input`.
+#[no_mangle]
+pub unsafe extern "C" fn lexiclean_cstr(
+  input: *const c_char,
+  out: *mut c_char,
+  out_len: usize,
+) -> isize {
+  if input.is_null() || out.is_null() {
+    return -1;
+  }
+
+  let input = CStr::from_ptr(input).to_bytes();
+  let out = slice::from_raw_parts_mut(out.cast::<u8>(), out_len);
+
+  match out
+    .split_last_mut()
+    .and_then(|(_, out)| lexiclean_to_buf(input, out).ok())
+  {
+    Some(len) => {
+      out[len] = 0;
+      len as isize
+    }
+    None => -1,
+  }
+}
+
+/// Clean the NUL-terminated path `input`, returning a newly allocated,
+/// NUL-terminated cleaned path, which must be freed with
+/// `lexiclean_cstr_free`, or null if `input` is null.
+///
+/// # Safety
+///
+/// `input` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn lexiclean_cstr_alloc(input: *const c_char) -> *mut c_char {
+  if input.is_null() {
+    return ptr::null_mut();
+  }
+
+  let input = CStr::from_ptr(input).to_bytes();
+  let mut out = vec![0; input.len() + 1];
+  let len = lexiclean_to_buf(input, &mut out)
+    .unwrap_or_else(|error| unreachable!("cleaning into a large enough buffer failed: {}", error));
+  out.truncate(len);
+
+  CString::new(out)
+    .unwrap_or_else(|error| unreachable!("cleaned path contains NUL: {}", error))
+    .into_raw()
+}
+
+/// Free a path returned by `lexiclean_cstr_alloc`. Does nothing if `path`
+/// is null.
+///
+/// # Safety
+///
+/// `path` must be null or have been returned by `lexiclean_cstr_alloc`, and
+/// not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn lexiclean_cstr_free(path: *mut c_char) {
+  if !path.is_null() {
+    drop(CString::from_raw(path));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn paths_are_cleaned_into_buffer() {
+    let mut out = [0x7f; 16];
+
+    let len = unsafe {
+      lexiclean_cstr(
+        b"/foo/./bar/..\0".as_ptr().cast(),
+        out.as_mut_ptr().cast(),
+        16,
+      )
+    };
+    assert_eq!(len, 4);
+    assert_eq!(&out[..5], b"/foo\0");
+
+    let len = unsafe { lexiclean_cstr(b"\0".as_ptr().cast(), out.as_mut_ptr().cast(), 2) };
+    assert_eq!(len, 1);
+    assert_eq!(&out[..2], b".\0");
+  }
+
+  #[test]
+  fn invalid_arguments_fail() {
+    let mut out = [0; 8];
+    unsafe {
+      assert_eq!(
+        lexiclean_cstr(b"foo\0".as_ptr().cast(), out.as_mut_ptr().cast(), 3),
+        -1
+      );
+      assert_eq!(
+        lexiclean_cstr(b"foo\0".as_ptr().cast(), out.as_mut_ptr().cast(), 0),
+        -1
+      );
+      assert_eq!(lexiclean_cstr(ptr::null(), out.as_mut_ptr().cast(), 8), -1);
+      assert_eq!(
+        lexiclean_cstr(b"foo\0".as_ptr().cast(), ptr::null_mut(), 8),
+        -1
+      );
+      assert!(lexiclean_cstr_alloc(ptr::null()).is_null());
+      lexiclean_cstr_free(ptr::null_mut());
+    }
+  }
+
+  #[test]
+  fn paths_are_cleaned_into_allocation() {
+    unsafe {
+      let path = lexiclean_cstr_alloc(b"foo//bar/../baz/\0".as_ptr().cast());
+      assert_eq!(CStr::from_ptr(path).to_bytes(), b"foo/baz");
+      lexiclean_cstr_free(path);
+    }
+  }
+}
@@ -0,0 +1,316 @@
+use {
+  crate::{pure_path::is_separator, Flavor, PureComponent, PurePath},
+  std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+  },
+};
+
+#[cfg(windows)]
+type Host = crate::Windows;
+
+#[cfg(not(windows))]
+type Host = crate::Posix;
+
+/// Convert an absolute path to a `file:` URL, cleaning it in the process, and
+/// percent-encoding characters that are not allowed in URL paths. Windows
+/// drive paths like `C:\foo` become `file:///C:/foo`, and UNC paths like
+/// `\\server\share\foo` become `file://server/share/foo`.
+///
+/// Returns `None` if `path` is relative or not valid Unicode. See
+/// `PurePath::to_file_url` for converting paths of other platforms.
+///
+/// ```
+/// # #[cfg(unix)]
+/// assert_eq!(
+///   lexiclean::to_file_url("/foo/../my file").unwrap(),
+///   "file:///my%20file",
+/// );
+/// ```
+pub fn to_file_url(path: impl AsRef<Path>) -> Option<String> {
+  PurePath::<Host>::new(path.as_ref().to_str()?).to_file_url()
+}
+
+/// Convert a `file:` URL to a path, percent-decoding and cleaning it in the
+/// process. See `PurePath::from_file_url`.
+///
+/// ```
+/// # #[cfg(unix)]
+/// assert_eq!(
+///   lexiclean::from_file_url("file:///foo/../my%20file").unwrap(),
+///   std::path::Path::new("/my file"),
+/// );
+/// ```
+pub fn from_file_url(url: &str) -> Option<PathBuf> {
+  PurePath::<Host>::from_file_url(url).map(|path| path.into_string().into())
+}
+
+impl<F: Flavor> PurePath<F> {
+  /// Convert an absolute path to a `file:` URL, like `to_file_url`. Returns
+  /// `None` if the path is relative, or is a Windows verbatim or device path.
+  pub fn to_file_url(&self) -> Option<String> {
+    use PureComponent::*;
+
+    let cleaned = self.lexiclean();
+
+    if !cleaned.is_absolute() {
+      return None;
+    }
+
+    let mut url = String::from("file://");
+    let mut components = cleaned.components();
+
+    if F::WINDOWS {
+      let prefix = match components.next() {
+        Some(Prefix(prefix)) => prefix,
+        _ => return None,
+      };
+
+      match prefix.as_bytes() {
+        [_, b':'] => {
+          url.push('/');
+          url.push_str(prefix);
+        }
+        [_, _, b'.' | b'?', separator, ..] if is_separator::<F>((*separator).into()) => {
+          return None
+        }
+        _ => {
+          let (server, share) = prefix[2..].split_once(is_separator::<F>)?;
+          encode(server, &mut url);
+          url.push('/');
+          encode(share, &mut url);
+        }
+      }
+    }
+
+    let mut empty = true;
+
+    for component in components {
+      if let Normal(name) = component {
+        url.push('/');
+        encode(name, &mut url);
+        empty = false;
+      }
+    }
+
+    if empty {
+      url.push('/');
+    }
+
+    Some(url)
+  }
+
+  /// Convert a `file:` URL to a path, percent-decoding and cleaning it in the
+  /// process. `file:///C:/foo`, `file:///C|/foo`, and `file:///C%3A/foo`
+  /// become `C:\foo` on Windows, and `file://server/share` becomes
+  /// `\\server\share`. A `localhost` host is ignored, and a query or
+  /// fragment is removed.
+  ///
+  /// Returns `None` if `url` is not a `file:` URL with an absolute path, has
+  /// a host which is not supported by the flavor, or has a percent-encoded
+  /// separator, NUL, or invalid UTF-8.
+  pub fn from_file_url(url: &str) -> Option<Self> {
+    if !url.get(..5)?.eq_ignore_ascii_case("file:") {
+      return None;
+    }
+
+    let rest = &url[5..];
+    let rest = &rest[..rest.find(['?', '#']).unwrap_or(rest.len())];
+
+    let (host, path) = match rest.strip_prefix("//") {
+      Some(rest) => rest.split_at(rest.find('/').unwrap_or(rest.len())),
+      None => ("", rest),
+    };
+
+    let host = if host.eq_ignore_ascii_case("localhost") {
+      String::new()
+    } else {
+      decode::<F>(host)?
+    };
+
+    let path = decode::<F>(path)?;
+
+    if !path.starts_with('/') {
+      return None;
+    }
+
+    if !F::WINDOWS {
+      return if host.is_empty() {
+        Some(Self::new(path).lexiclean())
+      } else {
+        None
+      };
+    }
+
+    if !host.is_empty() {
+      if path.len() == 1 {
+        return None;
+      }
+      return Some(Self::new(format!(r"\\{}{}", host, path)).lexiclean());
+    }
+
+    match path.as_bytes() {
+      [b'/', letter, b':' | b'|'] | [b'/', letter, b':' | b'|', b'/', ..]
+        if letter.is_ascii_alphabetic() =>
+      {
+        Some(
+          Self::new(format!(
+            "{}:/{}",
+            char::from(*letter),
+            path.get(4..).unwrap_or("")
+          ))
+          .lexiclean(),
+        )
+      }
+      _ => None,
+    }
+  }
+}
+
+fn encode(s: &str, url: &mut String) {
+  for &byte in s.as_bytes() {
+    if byte.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@".contains(&byte) {
+      url.push(byte.into());
+    } else {
+      write!(url, "%{:02X}", byte).unwrap();
+    }
+  }
+}
+
+fn decode<F: Flavor>(s: &str) -> Option<String> {
+  let mut bytes = Vec::with_capacity(s.len());
+  let mut rest = s.as_bytes();
+
+  while let Some((&byte, tail)) = rest.split_first() {
+    if byte != b'%' {
+      bytes.push(byte);
+      rest = tail;
+      continue;
+    }
+
+    let hex = tail
+      .get(..2)
+      .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+    let byte = u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?;
+
+    if byte == 0 || is_separator::<F>(byte.into()) {
+      return None;
+    }
+
+    bytes.push(byte);
+    rest = &tail[2..];
+  }
+
+  String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::{PurePosixPath, PureWindowsPath},
+  };
+
+  #[track_caller]
+  fn posix(path: &str, url: &str) {
+    assert_eq!(PurePosixPath::new(path).to_file_url().unwrap(), url);
+    assert_eq!(
+      PurePosixPath::from_file_url(url).unwrap(),
+      PurePosixPath::new(path).lexiclean(),
+    );
+  }
+
+  #[track_caller]
+  fn windows(path: &str, url: &str) {
+    assert_eq!(PureWindowsPath::new(path).to_file_url().unwrap(), url);
+    assert_eq!(
+      PureWindowsPath::from_file_url(url).unwrap(),
+      PureWindowsPath::new(path).lexiclean(),
+    );
+  }
+
+  #[test]
+  fn posix_paths_round_trip() {
+    posix("/", "file:///");
+    posix("/foo/bar", "file:///foo/bar");
+    posix("/foo/../bar/./baz/", "file:///bar/baz");
+    posix("/my file#1?.txt", "file:///my%20file%231%3F.txt");
+    posix("/100%", "file:///100%25");
+    posix("/é", "file:///%C3%A9");
+    posix(r"/a\b", "file:///a%5Cb");
+  }
+
+  #[test]
+  fn windows_paths_round_trip() {
+    windows(r"C:\", "file:///C:/");
+    windows(r"C:\foo\..\bar baz", "file:///C:/bar%20baz");
+    windows(r"c:/foo", "file:///c:/foo");
+    windows(r"\\server\share\foo", "file://server/share/foo");
+    windows(r"\\server\share\", "file://server/share/");
+  }
+
+  #[test]
+  fn relative_and_unsupported_paths_have_no_url() {
+    for path in ["foo", "", "../foo"] {
+      assert_eq!(PurePosixPath::new(path).to_file_url(), None, "{}", path);
+    }
+
+    for path in [r"C:foo", r"\foo", r"foo", r"\\?\C:\foo", r"\\.\pipe\foo"] {
+      assert_eq!(PureWindowsPath::new(path).to_file_url(), None, "{}", path);
+    }
+  }
+
+  #[test]
+  fn urls_are_decoded_leniently() {
+    let windows = |url: &str| PureWindowsPath::from_file_url(url).map(PurePath::into_string);
+    assert_eq!(windows("file:///C|/foo").unwrap(), r"C:\foo");
+    assert_eq!(windows("file:///c%3A/foo").unwrap(), r"c:\foo");
+    assert_eq!(
+      windows("FILE://localhost/C:/foo?query#fragment").unwrap(),
+      r"C:\foo"
+    );
+    assert_eq!(windows("file:/C:/foo/../bar").unwrap(), r"C:\bar");
+    assert_eq!(windows("file:///C:").unwrap(), r"C:\");
+
+    let posix = |url: &str| PurePosixPath::from_file_url(url).map(PurePath::into_string);
+    assert_eq!(posix("file://localhost/foo").unwrap(), "/foo");
+    assert_eq!(posix("file:/foo/./bar").unwrap(), "/foo/bar");
+  }
+
+  #[test]
+  fn invalid_urls_are_rejected() {
+    for url in [
+      "http:///foo",
+      "file:foo",
+      "file:///foo%2Fbar",
+      "file:///foo%00",
+      "file:///foo%FF",
+      "file:///foo%4",
+      "file:///foo%+1",
+      "file://server/foo",
+      "file",
+    ] {
+      assert_eq!(PurePosixPath::from_file_url(url), None, "{}", url);
+    }
+
+    for url in [
+      "file:///foo",
+      "file:///C:/foo%5Cbar",
+      "file://server",
+      "file://server/",
+    ] {
+      assert_eq!(PureWindowsPath::from_file_url(url), None, "{}", url);
+    }
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn host_paths_use_posix_flavor() {
+    assert_eq!(to_file_url("/foo/bar").unwrap(), "file:///foo/bar");
+    assert_eq!(to_file_url("foo"), None);
+    assert_eq!(
+      from_file_url("file:///foo/bar").unwrap(),
+      Path::new("/foo/bar")
+    );
+  }
+}
@@ -0,0 +1,26 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// Unicode normalization form to apply to components, with
+/// `Lexicleaner::normalize_unicode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Form {
+  /// Canonical composition, used by most tools.
+  Nfc,
+  /// Canonical decomposition, used by macOS for file names.
+  Nfd,
+  /// Compatibility composition.
+  Nfkc,
+  /// Compatibility decomposition.
+  Nfkd,
+}
+
+impl Form {
+  pub(crate) fn apply(self, s: &str) -> String {
+    match self {
+      Self::Nfc => s.nfc().collect(),
+      Self::Nfd => s.nfd().collect(),
+      Self::Nfkc => s.nfkc().collect(),
+      Self::Nfkd => s.nfkd().collect(),
+    }
+  }
+}
@@ -0,0 +1,79 @@
+use {
+  crate::{clean_components, Error},
+  std::path::{Component, Path},
+};
+
+/// Clean a native relative path into the form that Git and many manifest
+/// formats expect, relative to the root of the repository: `/` separators,
+/// with no `.` or `..` components, and no leading or trailing separator. The
+/// root of the repository itself is the empty string.
+///
+/// Fails with `Error::Absolute` if `path` is absolute or has a prefix, with
+/// `Error::Escape` if it ascends above the root of the repository, and with
+/// `Error::Unicode` if it is not valid Unicode.
+///
+/// ```
+/// use lexiclean::{to_git_path, Error};
+///
+/// assert_eq!(to_git_path("./src/../README.md"), Ok("README.md".into()));
+/// assert_eq!(to_git_path("../README.md"), Err(Error::Escape));
+/// ```
+pub fn to_git_path(path: impl AsRef<Path>) -> Result<String, Error> {
+  let mut git_path = String::new();
+
+  for component in clean_components(path.as_ref().components()) {
+    match component {
+      Component::Prefix(_) | Component::RootDir => return Err(Error::Absolute),
+      Component::ParentDir => return Err(Error::Escape),
+      Component::CurDir => {}
+      Component::Normal(name) => {
+        if !git_path.is_empty() {
+          git_path.push('/');
+        }
+        git_path.push_str(name.to_str().ok_or(Error::Unicode)?);
+      }
+    }
+  }
+
+  Ok(git_path)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn paths_are_converted() {
+    assert_eq!(to_git_path("foo/bar"), Ok("foo/bar".into()));
+    assert_eq!(to_git_path("./foo//bar/"), Ok("foo/bar".into()));
+    assert_eq!(to_git_path("foo/../bar"), Ok("bar".into()));
+    assert_eq!(to_git_path("foo/.."), Ok("".into()));
+    assert_eq!(to_git_path("."), Ok("".into()));
+    assert_eq!(to_git_path(""), Ok("".into()));
+  }
+
+  #[test]
+  fn escaping_and_absolute_paths_are_rejected() {
+    assert_eq!(to_git_path(".."), Err(Error::Escape));
+    assert_eq!(to_git_path("foo/../../bar"), Err(Error::Escape));
+    assert_eq!(to_git_path("/foo"), Err(Error::Absolute));
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn non_unicode_paths_are_rejected() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    assert_eq!(
+      to_git_path(OsStr::from_bytes(b"foo/\xff")),
+      Err(Error::Unicode),
+    );
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn backslashes_are_converted() {
+    assert_eq!(to_git_path(r"foo\.\bar\..\baz"), Ok("foo/baz".into()));
+    assert_eq!(to_git_path(r"C:foo"), Err(Error::Absolute));
+  }
+}
@@ -0,0 +1,170 @@
+use {
+  crate::{clean_components, Error, Lexiclean, Lexicleaner},
+  std::path::{Component, Path, PathBuf},
+};
+
+/// A root directory that untrusted relative paths are confined to.
+///
+/// The guarantee is purely lexical: paths returned by `Jail::resolve` are
+/// lexically inside the root, but symlinks under the root may still point
+/// outside of it.
+///
+/// ```
+/// use lexiclean::{Error, Jail};
+///
+/// let jail = Jail::new("/srv/www");
+///
+/// assert_eq!(jail.resolve("css/../index.html"), Ok("/srv/www/index.html".into()));
+/// assert_eq!(jail.resolve("../etc/passwd"), Err(Error::Escape));
+/// assert_eq!(jail.resolve("/etc/passwd"), Err(Error::Absolute));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Jail {
+  root: PathBuf,
+}
+
+impl Jail {
+  pub fn new(root: impl AsRef<Path>) -> Self {
+    Self {
+      root: root.as_ref().lexiclean(),
+    }
+  }
+
+  /// The cleaned root directory.
+  pub fn root(&self) -> &Path {
+    &self.root
+  }
+
+  /// Join `untrusted` onto the root and clean the result, failing with
+  /// `Error::Absolute` if `untrusted` is absolute or has a prefix, and with
+  /// `Error::Escape` if it would ascend above the root.
+  pub fn resolve(&self, untrusted: impl AsRef<Path>) -> Result<PathBuf, Error> {
+    let untrusted = untrusted.as_ref();
+
+    if let Some(Component::Prefix(_)) | Some(Component::RootDir) = untrusted.components().next() {
+      return Err(Error::Absolute);
+    }
+
+    let cleaned = clean_components(untrusted.components());
+
+    if cleaned.first() == Some(&Component::ParentDir) {
+      return Err(Error::Escape);
+    }
+
+    Ok(
+      clean_components(self.root.components().chain(cleaned))
+        .into_iter()
+        .collect(),
+    )
+  }
+}
+
+/// Resolve the untrusted path of an HTTP request, like `/css/site.css`,
+/// against the document root `root`, for static file servers.
+///
+/// Leading `/`s are removed, and the rest of `request_path` is joined onto
+/// `root` with `Jail::resolve`, so the result is lexically inside `root`.
+/// `request_path` is used as is, so it should be percent-decoded first, if
+/// at all, for example with `resolve_encoded_under_root`, and an encoded
+/// `%2e%2e` is an ordinary file name. Fails like
+/// `Jail::resolve`, with `Error::Absolute` if what remains is absolute or
+/// has a Windows prefix, and like `Lexicleaner::deny_control_characters` if
+/// a component contains a control character. On Windows, components that
+/// are reserved device names or contain `:` are also rejected, since they
+/// would open a device or an alternate data stream instead of a file.
+///
+/// ```
+/// use lexiclean::{resolve_under_root, Error};
+///
+/// assert_eq!(
+///   resolve_under_root("/srv/www", "/css/../index.html"),
+///   Ok("/srv/www/index.html".into()),
+/// );
+/// assert_eq!(resolve_under_root("/srv/www", "/../etc/passwd"), Err(Error::Escape));
+/// ```
+pub fn resolve_under_root(root: impl AsRef<Path>, request_path: &str) -> Result<PathBuf, Error> {
+  let untrusted = Path::new(request_path.trim_start_matches('/'));
+
+  Lexicleaner::new()
+    .deny_control_characters(true)
+    .deny_reserved_names(cfg!(windows))
+    .deny_streams(cfg!(windows))
+    .try_clean(untrusted)?;
+
+  Jail::new(root).resolve(untrusted)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn paths_inside_root_are_resolved() {
+    let jail = Jail::new("/foo/./bar/");
+    assert_eq!(jail.root(), Path::new("/foo/bar"));
+    assert_eq!(jail.resolve("baz"), Ok("/foo/bar/baz".into()));
+    assert_eq!(jail.resolve("baz/../bob"), Ok("/foo/bar/bob".into()));
+    assert_eq!(jail.resolve(""), Ok("/foo/bar".into()));
+    assert_eq!(jail.resolve("baz/.."), Ok("/foo/bar".into()));
+  }
+
+  #[test]
+  fn escaping_paths_are_rejected() {
+    let jail = Jail::new("/foo");
+    assert_eq!(jail.resolve(".."), Err(Error::Escape));
+    assert_eq!(jail.resolve("bar/../../foo"), Err(Error::Escape));
+  }
+
+  #[test]
+  fn absolute_paths_are_rejected() {
+    assert_eq!(Jail::new("/foo").resolve("/foo/bar"), Err(Error::Absolute));
+  }
+
+  #[test]
+  fn relative_roots_stay_clean() {
+    assert_eq!(Jail::new("").resolve("foo"), Ok("foo".into()));
+    assert_eq!(Jail::new("..").resolve("foo"), Ok("../foo".into()));
+  }
+
+  #[test]
+  fn request_paths_are_resolved_under_root() {
+    for (request_path, want) in [
+      ("/", Ok("/srv/www")),
+      ("", Ok("/srv/www")),
+      ("/index.html", Ok("/srv/www/index.html")),
+      ("//css//site.css", Ok("/srv/www/css/site.css")),
+      ("/css/../index.html", Ok("/srv/www/index.html")),
+      ("/%2e%2e/etc", Ok("/srv/www/%2e%2e/etc")),
+      ("/..", Err(Error::Escape)),
+      ("/css/../../etc/passwd", Err(Error::Escape)),
+      ("/foo\0", Err(Error::Nul)),
+      ("/foo\r\nbar", Err(Error::ControlCharacter)),
+    ] {
+      assert_eq!(
+        resolve_under_root("/srv/www", request_path),
+        want.map(PathBuf::from),
+        "{:?}",
+        request_path
+      );
+    }
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn windows_request_paths_are_rejected() {
+    for (request_path, want) in [
+      (r"/C:\Windows", Error::Absolute),
+      (r"/\\server\share", Error::Absolute),
+      (r"/..\..\secret", Error::Escape),
+      ("/nul.txt", Error::ReservedName),
+      ("/index.html::$DATA", Error::Stream),
+    ] {
+      assert_eq!(
+        resolve_under_root(r"C:\srv", request_path),
+        Err(want),
+        "{:?}",
+        request_path
+      );
+    }
+  }
+}
@@ -0,0 +1,205 @@
+use {
+  crate::Lexiclean,
+  std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+  },
+};
+
+/// A path which compares, orders, and hashes by its cleaned form, for use
+/// as a map or set key, so that `foo/./bar` and `foo/bar` are the same key.
+///
+/// The path is cleaned once, on construction, and the original path is kept
+/// for display. `LexicalKey` borrows as its cleaned form, so maps can be
+/// queried with a cleaned `&Path`, without constructing a `LexicalKey`.
+///
+/// ```
+/// use lexiclean::{LexicalKey, Lexiclean};
+/// use std::{collections::HashMap, path::Path};
+///
+/// let mut sizes = HashMap::new();
+/// sizes.insert(LexicalKey::new("foo/./bar"), 100);
+///
+/// assert_eq!(sizes.get(&LexicalKey::new("foo/baz/../bar/")), Some(&100));
+/// assert_eq!(sizes.get(&*Path::new("foo//bar").lexiclean()), Some(&100));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LexicalKey {
+  cleaned: PathBuf,
+  original: PathBuf,
+}
+
+impl LexicalKey {
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    let original = path.into();
+    Self {
+      cleaned: original.as_path().lexiclean(),
+      original,
+    }
+  }
+
+  /// Construct a key whose cleaned form is also case-folded with
+  /// `Lexicleaner::fold_case`, for paths on case-insensitive filesystems, so
+  /// that `Foo/BAR` and `foo/bar` are the same key. Keys constructed with
+  /// `new` and `case_insensitive` should not be mixed in the same map.
+  #[cfg(feature = "case-fold")]
+  pub fn case_insensitive(path: impl Into<PathBuf>) -> Self {
+    let original = path.into();
+    Self {
+      cleaned: crate::Lexicleaner::new().fold_case(true).clean(&original),
+      original,
+    }
+  }
+
+  /// The cleaned path, by which keys are compared.
+  pub fn cleaned(&self) -> &Path {
+    &self.cleaned
+  }
+
+  /// The path the key was constructed from.
+  pub fn original(&self) -> &Path {
+    &self.original
+  }
+
+  pub fn into_original(self) -> PathBuf {
+    self.original
+  }
+}
+
+impl PartialEq for LexicalKey {
+  fn eq(&self, other: &Self) -> bool {
+    self.cleaned == other.cleaned
+  }
+}
+
+impl Eq for LexicalKey {}
+
+impl PartialOrd for LexicalKey {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for LexicalKey {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.cleaned.cmp(&other.cleaned)
+  }
+}
+
+impl Hash for LexicalKey {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.cleaned.hash(state);
+  }
+}
+
+impl Borrow<Path> for LexicalKey {
+  fn borrow(&self) -> &Path {
+    &self.cleaned
+  }
+}
+
+impl From<PathBuf> for LexicalKey {
+  fn from(path: PathBuf) -> Self {
+    Self::new(path)
+  }
+}
+
+impl From<&Path> for LexicalKey {
+  fn from(path: &Path) -> Self {
+    Self::new(path)
+  }
+}
+
+/// Compare `a` and `b` by the components of their cleaned forms, without
+/// allocating, so that paths sort the same way regardless of redundant
+/// components and which separators they use. A path sorts before paths
+/// inside of it, so `foo` sorts before `foo/bar`, and `foo/bar` before
+/// `foo-bar`, and absolute paths sort before relative paths.
+/// `LexicalKey`s are ordered the same way.
+///
+/// ```
+/// use lexiclean::cmp_lexical;
+///
+/// let mut paths = vec!["foo-bar", "foo/./bar", "foo/", "/baz", "foo/..//a"];
+/// paths.sort_by(|a, b| cmp_lexical(a, b));
+/// assert_eq!(paths, ["/baz", "foo/..//a", "foo/", "foo/./bar", "foo-bar"]);
+/// ```
+pub fn cmp_lexical(a: impl AsRef<Path>, b: impl AsRef<Path>) -> Ordering {
+  a.as_ref()
+    .lexiclean_components()
+    .cmp(b.as_ref().lexiclean_components())
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    std::collections::{BTreeSet, HashSet},
+  };
+
+  #[test]
+  fn keys_compare_by_cleaned_form() {
+    assert_eq!(LexicalKey::new("foo/./bar"), LexicalKey::new("foo//bar/"));
+    assert_eq!(LexicalKey::new("foo/.."), LexicalKey::new(""));
+    assert_ne!(LexicalKey::new("foo"), LexicalKey::new("bar"));
+    assert!(LexicalKey::new("a/../b") > LexicalKey::new("a"));
+  }
+
+  #[test]
+  #[cfg(feature = "case-fold")]
+  fn case_insensitive_keys_compare_by_folded_form() {
+    let key = LexicalKey::case_insensitive("Foo/./BÄR");
+    assert_eq!(key, LexicalKey::case_insensitive("foo/bär/"));
+    assert_ne!(key, LexicalKey::case_insensitive("foo/baz"));
+    assert_eq!(key.cleaned(), Path::new("foo/bär"));
+    assert_eq!(key.original().as_os_str(), "Foo/./BÄR");
+
+    let mut keys = HashSet::new();
+    keys.insert(key);
+    assert!(keys.contains(Path::new("foo/bär")));
+  }
+
+  #[test]
+  fn paths_are_compared_by_cleaned_components() {
+    for (a, b, ordering) in [
+      ("foo", "foo/bar", Ordering::Less),
+      ("foo/bar", "foo-bar", Ordering::Less),
+      ("foo/./bar", "foo//bar/", Ordering::Equal),
+      ("a/../b", "a", Ordering::Greater),
+      ("/z", "a", Ordering::Less),
+      ("", ".", Ordering::Equal),
+      ("..", "a", Ordering::Less),
+    ] {
+      assert_eq!(cmp_lexical(a, b), ordering, "{} {}", a, b);
+      assert_eq!(
+        LexicalKey::new(a).cmp(&LexicalKey::new(b)),
+        ordering,
+        "{} {}",
+        a,
+        b
+      );
+    }
+  }
+
+  #[test]
+  fn original_is_kept() {
+    let key = LexicalKey::new("foo/./bar");
+    assert_eq!(key.cleaned(), Path::new("foo/bar"));
+    assert_eq!(key.original().as_os_str(), "foo/./bar");
+  }
+
+  #[test]
+  fn sets_deduplicate_equivalent_paths() {
+    let paths = ["foo/bar", "foo/./bar", "foo/baz/../bar", "foo"];
+
+    let hashed = paths.iter().map(LexicalKey::new).collect::<HashSet<_>>();
+    assert_eq!(hashed.len(), 2);
+    assert!(hashed.contains(Path::new("foo/bar")));
+
+    let ordered = paths.iter().map(LexicalKey::new).collect::<BTreeSet<_>>();
+    assert_eq!(ordered.len(), 2);
+    assert!(ordered.contains(Path::new("foo")));
+  }
+}
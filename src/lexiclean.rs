@@ -0,0 +1,924 @@
+use {
+  crate::{relative, AncestorsLexical, Error, LexicleanComponents, Lexicleaner},
+  std::{
+    env,
+    ffi::{OsStr, OsString},
+    io,
+    path::{Component, Path, PathBuf, Prefix, MAIN_SEPARATOR},
+    rc::Rc,
+    sync::Arc,
+  },
+};
+
+pub trait Lexiclean: AsRef<Path> {
+  /// The type of the cleaned path. `PathBuf` for everything except smart
+  /// pointers, which clean to the same kind of smart pointer.
+  type Output;
+
+  fn lexiclean(self) -> Self::Output;
+
+  /// Clean `self` into `out`, replacing its contents but reusing its
+  /// allocation.
+  fn lexiclean_into(&self, out: &mut PathBuf) {
+    Lexicleaner::new().clean_into(self, out);
+  }
+
+  /// Clean `self`, failing with `Error::Escape` if a `..` would ascend above
+  /// the root, instead of dropping it.
+  fn lexiclean_strict(&self) -> Result<PathBuf, Error> {
+    Lexicleaner::new().deny_escape(true).try_clean(self)
+  }
+
+  /// The relative path from `base` to `self`, computed from the cleaned forms
+  /// of both paths, like C++'s `std::filesystem::path::lexically_relative`.
+  ///
+  /// Returns `None` if `self` and `base` have different roots or prefixes, or
+  /// if `base` has more leading `..` components than `self`, since then the
+  /// relative path depends on the names of directories not in `base`.
+  fn lexically_relative<P: AsRef<Path>>(&self, base: P) -> Option<PathBuf> {
+    relative::lexically_relative(self.as_ref(), base.as_ref())
+  }
+
+  /// Like `lexically_relative`, but returns `self` unchanged instead of
+  /// `None` when there is no relative path from `base` to `self`, like C++'s
+  /// `std::filesystem::path::lexically_proximate`.
+  fn lexically_proximate<P: AsRef<Path>>(&self, base: P) -> PathBuf {
+    let path = self.as_ref();
+    path
+      .lexically_relative(base)
+      .unwrap_or_else(|| path.to_owned())
+  }
+
+  /// Returns `true` if the cleaned form of `self` is lexically inside, or
+  /// equal to, the cleaned form of `ancestor`.
+  ///
+  /// Remaining `..` components are handled conservatively: `false` is
+  /// returned whenever the answer depends on the names of directories not
+  /// present in either path, so `a` is not within `../b`.
+  fn is_within<P: AsRef<Path>>(&self, ancestor: P) -> bool {
+    relative::is_within(self.as_ref(), ancestor.as_ref())
+  }
+
+  /// The number of levels above its starting point that the cleaned form of
+  /// `self` reaches, which is the number of leading `..` components it has,
+  /// so `../../a` and `a/../../..` both have an escape depth of 2. Paths with
+  /// a root have an escape depth of 0, since `..` components which would
+  /// ascend above the root are dropped. Computed without cleaning `self`.
+  fn escape_depth(&self) -> usize {
+    let mut depth = 0;
+    let mut normals = 0usize;
+    let mut rooted = false;
+
+    for component in self.as_ref().components() {
+      match component {
+        Component::ParentDir => match normals.checked_sub(1) {
+          Some(parent) => normals = parent,
+          None if !rooted => depth += 1,
+          None => {}
+        },
+        Component::Normal(_) => normals += 1,
+        Component::CurDir => {}
+        Component::Prefix(_) | Component::RootDir => rooted = is_root(&component),
+      }
+    }
+
+    depth
+  }
+
+  /// Returns `true` if the cleaned form of `self` ascends above its
+  /// starting point, which is when `escape_depth` is not 0.
+  fn escapes(&self) -> bool {
+    self.escape_depth() > 0
+  }
+
+  /// Resolve `self` against `base`, joining and cleaning without making any
+  /// system calls. If `self` is absolute, `base` is ignored. The result is
+  /// absolute if `base` is absolute.
+  fn absolutize_with<P: AsRef<Path>>(&self, base: P) -> PathBuf {
+    clean_join(base, self)
+  }
+
+  /// Resolve `self` against the current directory, joining and cleaning. The
+  /// current directory is only fetched if `self` is relative, and fetching it
+  /// is the only way this can fail. Unlike `absolute`, an empty path is
+  /// resolved to the current directory.
+  fn lexiclean_absolute(&self) -> io::Result<PathBuf> {
+    let path = self.as_ref();
+
+    if path.is_absolute() {
+      Ok(path.lexiclean())
+    } else {
+      Ok(path.absolutize_with(env::current_dir()?))
+    }
+  }
+
+  /// The parent of the cleaned form of `self`.
+  ///
+  /// Unlike `Path::parent`, `..` components are understood: the parent of
+  /// `foo/..` is `..`, the parent of `foo` is `.`, and the parent of a path
+  /// ending in `..` is that path with another `..` appended. Roots have no
+  /// parent, but the parent of the drive-relative Windows path `C:` is `C:..`.
+  fn parent_lexical(&self) -> Option<PathBuf> {
+    let mut path = self.as_ref().lexiclean();
+
+    match path.components().next_back() {
+      Some(Component::Normal(_)) => {
+        path.pop();
+        if path.as_os_str().is_empty() {
+          path.push(Component::CurDir);
+        }
+      }
+      Some(Component::CurDir) => path = Component::ParentDir.as_os_str().into(),
+      Some(Component::ParentDir) => path.push(Component::ParentDir),
+      Some(prefix @ Component::Prefix(_)) if !is_root(&prefix) => path.push(Component::ParentDir),
+      Some(Component::Prefix(_)) | Some(Component::RootDir) | None => return None,
+    }
+
+    Some(path)
+  }
+
+  /// Iterate over the cleaned form of `self` and its ancestors, cleaning only
+  /// once. `a/b/../c` yields `a/c`, `a`, and `.`.
+  fn ancestors_lexical(&self) -> AncestorsLexical {
+    AncestorsLexical::new(self.as_ref().lexiclean())
+  }
+
+  /// Clean `self` and lowercase its `Normal` components, producing a key for
+  /// comparing paths on case-insensitive filesystems. See
+  /// `Lexicleaner::fold_case`.
+  #[cfg(feature = "case-fold")]
+  fn lexiclean_folded(&self) -> PathBuf {
+    Lexicleaner::new().fold_case(true).clean(self)
+  }
+
+  /// Returns `true` if `self` is already clean, that is, if `lexiclean` would
+  /// return a path with exactly the same bytes as `self`. Does not allocate.
+  fn is_clean(&self) -> bool {
+    use Component::*;
+
+    let path = self.as_ref();
+
+    if is_verbatim(path) {
+      return true;
+    }
+
+    let mut rest = path.as_os_str().as_encoded_bytes();
+    let mut previous = None;
+
+    for component in path.components() {
+      match (previous, component) {
+        (Some(CurDir), _) | (Some(_), CurDir) => return false,
+        (Some(previous), ParentDir) if matches!(previous, Normal(_)) || is_root(&previous) => {
+          return false
+        }
+        _ => {}
+      }
+
+      if let Some(Normal(_)) | Some(ParentDir) = previous {
+        match rest.split_first() {
+          Some((&separator, tail)) if char::from(separator) == MAIN_SEPARATOR => rest = tail,
+          _ => return false,
+        }
+      }
+
+      match rest.strip_prefix(component.as_os_str().as_encoded_bytes()) {
+        Some(tail) => rest = tail,
+        None => return false,
+      }
+
+      previous = Some(component);
+    }
+
+    previous.is_some() && rest.is_empty()
+  }
+
+  /// Clean `self`, also returning whether cleaning changed any bytes of the
+  /// path.
+  fn lexiclean_changed(&self) -> (PathBuf, bool) {
+    let path = self.as_ref();
+
+    if path.is_clean() {
+      (path.to_owned(), false)
+    } else {
+      (path.lexiclean(), true)
+    }
+  }
+
+  /// Iterate over the components of the cleaned path, without allocating.
+  fn lexiclean_components(&self) -> LexicleanComponents<'_> {
+    LexicleanComponents::new(self.as_ref().components())
+  }
+}
+
+/// Clean a sequence of path components, returning the cleaned sequence.
+///
+/// This is the algorithm used by `Lexiclean::lexiclean`, exposed so that it
+/// can be used with components that do not come from a single `Path`. If
+/// `components` is empty, or every component is removed, a single
+/// `Component::CurDir` is returned.
+pub fn clean_components<'a, I>(components: I) -> Vec<Component<'a>>
+where
+  I: IntoIterator<Item = Component<'a>>,
+{
+  Lexicleaner::new()
+    .clean_components(components, false)
+    .unwrap_or_else(|error| unreachable!("unchecked cleaning failed: {}", error))
+}
+
+/// Returns `true` if `component` is a root, above which `..` cannot ascend.
+/// The server and share of a Windows UNC path, like `\\server\share`, are part
+/// of its prefix, so `..` cannot pop the share, and the same is true of the
+/// device of a DOS device path, like `\\.\pipe`. Windows drive prefixes
+/// without a root directory, like `C:`, are relative to the current directory
+/// of the drive, so they are not roots.
+pub(crate) fn is_root(component: &Component) -> bool {
+  match component {
+    Component::RootDir => true,
+    Component::Prefix(prefix) => !matches!(prefix.kind(), Prefix::Disk(_)),
+    Component::CurDir | Component::ParentDir | Component::Normal(_) => false,
+  }
+}
+
+/// Fail with `Error::Nul` if `path` contains a NUL byte.
+pub(crate) fn check_nul(path: &Path) -> Result<(), Error> {
+  if path.as_os_str().as_encoded_bytes().contains(&0) {
+    Err(Error::Nul)
+  } else {
+    Ok(())
+  }
+}
+
+/// Returns `true` if `path` has a Windows verbatim prefix, like `\\?\C:`, in
+/// which `.` and `..` are ordinary names.
+pub(crate) fn is_verbatim(path: &Path) -> bool {
+  match path.components().next() {
+    Some(Component::Prefix(prefix)) => prefix.kind().is_verbatim(),
+    _ => false,
+  }
+}
+
+/// Join `tail` onto `base` and clean the result, in a single pass over their
+/// components, without building the joined path. As with `Path::join`, if
+/// `tail` is absolute, it replaces `base`.
+pub fn clean_join(base: impl AsRef<Path>, tail: impl AsRef<Path>) -> PathBuf {
+  let base = base.as_ref();
+  let tail = tail.as_ref();
+
+  let base = match tail.components().next() {
+    Some(Component::Prefix(_)) => Path::new(""),
+    Some(Component::RootDir) => match base.components().next() {
+      Some(prefix @ Component::Prefix(_)) => Path::new(prefix.as_os_str()),
+      _ => Path::new(""),
+    },
+    _ => base,
+  };
+
+  // A separator is added between `base` and `tail`, or if both are empty,
+  // the result is `.`.
+  let mut out = PathBuf::with_capacity(base.as_os_str().len() + tail.as_os_str().len() + 1);
+  Lexicleaner::new().write_components(base.components().chain(tail.components()), &mut out);
+  out
+}
+
+impl Lexiclean for &Path {
+  type Output = PathBuf;
+
+  fn lexiclean(self) -> PathBuf {
+    let mut path = PathBuf::new();
+    self.lexiclean_into(&mut path);
+    path
+  }
+}
+
+impl Lexiclean for &PathBuf {
+  type Output = PathBuf;
+
+  fn lexiclean(self) -> PathBuf {
+    self.as_path().lexiclean()
+  }
+}
+
+impl Lexiclean for PathBuf {
+  type Output = PathBuf;
+
+  #[cfg(unix)]
+  fn lexiclean(self) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+
+    let mut bytes = self.into_os_string().into_vec();
+    crate::unix::clean_in_place(&mut bytes);
+    OsString::from_vec(bytes).into()
+  }
+
+  #[cfg(not(unix))]
+  fn lexiclean(self) -> PathBuf {
+    self.as_path().lexiclean()
+  }
+}
+
+impl Lexiclean for &str {
+  type Output = PathBuf;
+
+  fn lexiclean(self) -> PathBuf {
+    Path::new(self).lexiclean()
+  }
+}
+
+impl Lexiclean for &String {
+  type Output = PathBuf;
+
+  fn lexiclean(self) -> PathBuf {
+    Path::new(self).lexiclean()
+  }
+}
+
+impl Lexiclean for String {
+  type Output = PathBuf;
+
+  fn lexiclean(self) -> PathBuf {
+    PathBuf::from(self).lexiclean()
+  }
+}
+
+impl Lexiclean for &OsStr {
+  type Output = PathBuf;
+
+  fn lexiclean(self) -> PathBuf {
+    Path::new(self).lexiclean()
+  }
+}
+
+impl Lexiclean for &OsString {
+  type Output = PathBuf;
+
+  fn lexiclean(self) -> PathBuf {
+    Path::new(self).lexiclean()
+  }
+}
+
+impl Lexiclean for OsString {
+  type Output = PathBuf;
+
+  fn lexiclean(self) -> PathBuf {
+    PathBuf::from(self).lexiclean()
+  }
+}
+
+impl Lexiclean for Box<Path> {
+  type Output = Box<Path>;
+
+  fn lexiclean(self) -> Box<Path> {
+    if self.is_clean() {
+      self
+    } else {
+      PathBuf::from(self).lexiclean().into_boxed_path()
+    }
+  }
+}
+
+impl Lexiclean for &Box<Path> {
+  type Output = Box<Path>;
+
+  fn lexiclean(self) -> Box<Path> {
+    self.as_ref().lexiclean().into_boxed_path()
+  }
+}
+
+impl Lexiclean for Rc<Path> {
+  type Output = Rc<Path>;
+
+  fn lexiclean(self) -> Rc<Path> {
+    (&self).lexiclean()
+  }
+}
+
+impl Lexiclean for &Rc<Path> {
+  type Output = Rc<Path>;
+
+  fn lexiclean(self) -> Rc<Path> {
+    if self.is_clean() {
+      Rc::clone(self)
+    } else {
+      self.as_ref().lexiclean().into()
+    }
+  }
+}
+
+impl Lexiclean for Arc<Path> {
+  type Output = Arc<Path>;
+
+  fn lexiclean(self) -> Arc<Path> {
+    (&self).lexiclean()
+  }
+}
+
+impl Lexiclean for &Arc<Path> {
+  type Output = Arc<Path>;
+
+  fn lexiclean(self) -> Arc<Path> {
+    if self.is_clean() {
+      Arc::clone(self)
+    } else {
+      self.as_ref().lexiclean().into()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    std::{
+      alloc::{GlobalAlloc, Layout, System},
+      cell::Cell,
+    },
+  };
+
+  thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+  }
+
+  struct CountingAllocator;
+
+  impl CountingAllocator {
+    fn count() {
+      let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+    }
+  }
+
+  // SAFETY: All allocation is delegated to `System`.
+  unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+      Self::count();
+      System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+      System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+      Self::count();
+      System.realloc(ptr, layout, new_size)
+    }
+  }
+
+  #[global_allocator]
+  static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+  fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+  }
+
+  #[track_caller]
+  fn case(path: &str, want: &str) {
+    assert_eq!(Path::new(path).lexiclean(), Path::new(want));
+    assert_eq!(
+      PathBuf::from(path).lexiclean().as_os_str(),
+      Path::new(path).lexiclean().as_os_str(),
+    );
+
+    assert!(Path::new(path)
+      .lexiclean_components()
+      .eq(Path::new(want).components()));
+
+    assert_eq!(Path::new(path).is_clean(), path == want);
+    assert!(Path::new(want).is_clean());
+
+    assert_eq!(
+      Path::new(path).lexiclean_changed(),
+      (PathBuf::from(want), path != want),
+    );
+
+    let mut out = PathBuf::from("garbage");
+    Path::new(path).lexiclean_into(&mut out);
+    assert_eq!(out, Path::new(want));
+  }
+
+  #[test]
+  fn empty_path_maps_to_current_dir() {
+    case("", ".");
+  }
+
+  #[test]
+  fn single_current_dir_is_preserved() {
+    case(".", ".");
+  }
+
+  #[test]
+  fn multiple_current_dirs_are_collapsed() {
+    case("././.", ".");
+  }
+
+  #[test]
+  fn leading_parent_dir_is_preserved() {
+    case("..", "..");
+  }
+
+  #[test]
+  fn multiple_parent_dirs_are_preserved() {
+    case("../../..", "../../..");
+  }
+
+  #[test]
+  fn trailing_slash_is_removed() {
+    case("foo/", "foo");
+  }
+
+  #[test]
+  fn leading_current_dir_is_removed() {
+    case("./foo", "foo");
+  }
+
+  #[test]
+  fn trailing_parent_dir_after_current_dir_is_preserved() {
+    case("./..", "..");
+  }
+
+  #[test]
+  fn trailing_current_dir_is_removed() {
+    case("foo/.", "foo");
+  }
+
+  #[test]
+  fn intermediate_current_dir_is_removed() {
+    case("foo/./bar", "foo/bar");
+  }
+
+  #[test]
+  fn multiple_current_dirs_are_removed() {
+    case("././.", ".");
+  }
+
+  #[test]
+  fn parent_dir_after_root_is_removed() {
+    case("/..", "/");
+  }
+
+  #[test]
+  fn current_dir_after_root_is_removed() {
+    case("/.", "/");
+  }
+
+  #[test]
+  fn multiple_slashes_are_removed() {
+    case("//foo//bar//", "/foo/bar");
+  }
+
+  #[test]
+  fn normal_after_root_is_preserved() {
+    case("/foo", "/foo");
+  }
+
+  #[test]
+  fn intermediate_parent_dir_is_removed() {
+    case("/foo/../bar", "/bar");
+  }
+
+  #[test]
+  fn trailing_parent_dir_pops_normal() {
+    case("/foo/bar/..", "/foo");
+  }
+
+  #[test]
+  fn trailing_parent_dir_pops_normal_before_current() {
+    case("/foo/bar/./..", "/foo");
+  }
+
+  #[test]
+  fn normal_is_preserved() {
+    case("foo", "foo");
+  }
+
+  #[test]
+  fn parent_dir_after_normal_is_current() {
+    case("foo/..", ".");
+  }
+
+  #[test]
+  fn parent_dir_after_leading_parent_dir_is_preserved() {
+    case("../foo/../..", "../..");
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn path_buf_is_cleaned_in_place() {
+    let mut path = PathBuf::with_capacity(64);
+    path.push("foo/./bar/../baz");
+    let capacity = path.capacity();
+    let cleaned = path.lexiclean();
+    assert_eq!(cleaned, Path::new("foo/baz"));
+    assert_eq!(cleaned.capacity(), capacity);
+  }
+
+  #[test]
+  fn lexiclean_into_reuses_buffer() {
+    let mut out = PathBuf::with_capacity(64);
+    let capacity = out.capacity();
+    Path::new("foo/../bar").lexiclean_into(&mut out);
+    Path::new("/baz/./bob").lexiclean_into(&mut out);
+    assert_eq!(out, Path::new("/baz/bob"));
+    assert_eq!(out.capacity(), capacity);
+  }
+
+  #[test]
+  fn deep_paths_are_cleaned_without_allocating() {
+    let path = (0..100)
+      .map(|i| ["foo", ".", "..", "bar", ""][i % 5])
+      .collect::<Vec<&str>>()
+      .join("/");
+    let mut out = PathBuf::with_capacity(path.len() + 2);
+
+    assert_eq!(allocations(|| drop(Path::new("foo").lexiclean())), 1);
+
+    for path in [
+      &path[..],
+      "foo",
+      "a/b/c/d/e/f/g/h/i/j/k/l/m/n/o/p/q/r/s/t/u/v/w/x/y/z/../..",
+    ] {
+      assert_eq!(allocations(|| Path::new(path).lexiclean_into(&mut out)), 0);
+      assert_eq!(out, Path::new(path).lexiclean());
+    }
+  }
+
+  #[test]
+  fn output_is_allocated_once() {
+    let path = "foo/./bar/".repeat(100);
+    assert_eq!(allocations(|| drop(Path::new(&path).lexiclean())), 1);
+    assert_eq!(allocations(|| drop(Path::new("").lexiclean())), 1);
+    assert_eq!(
+      allocations(|| drop(Lexicleaner::new().leading_current_dir(true).clean("foo"))),
+      1
+    );
+    assert_eq!(allocations(|| drop(clean_join(&path, &path))), 1);
+  }
+
+  #[test]
+  fn path_buf_reference_is_not_consumed() {
+    let path = PathBuf::from("foo/..");
+    assert_eq!((&path).lexiclean(), Path::new("."));
+    assert_eq!(path, Path::new("foo/.."));
+  }
+
+  #[test]
+  fn is_clean_checks_bytes() {
+    assert!(!Path::new("").is_clean());
+    assert!(!Path::new("foo//bar").is_clean());
+    assert!(!Path::new("./foo").is_clean());
+    assert!(!Path::new("/../foo").is_clean());
+    assert!(Path::new("../foo").is_clean());
+  }
+
+  #[test]
+  fn components_from_multiple_paths_can_be_cleaned() {
+    assert_eq!(
+      clean_components(
+        Path::new("foo/bar")
+          .components()
+          .chain(Path::new("../baz").components())
+      )
+      .into_iter()
+      .collect::<PathBuf>(),
+      Path::new("foo/baz"),
+    );
+  }
+
+  #[test]
+  fn smart_pointers_clean_to_the_same_smart_pointer() {
+    let boxed: Box<Path> = Path::new("foo/../bar").into();
+    assert_eq!(boxed.lexiclean(), Path::new("bar").into());
+
+    let rc: Rc<Path> = Path::new("foo/../bar").into();
+    assert_eq!(rc.lexiclean(), Path::new("bar").into());
+
+    let arc: Arc<Path> = Path::new("foo/../bar").into();
+    assert_eq!(arc.lexiclean(), Path::new("bar").into());
+  }
+
+  #[test]
+  fn clean_shared_paths_are_not_reallocated() {
+    let rc: Rc<Path> = Path::new("foo/bar").into();
+    assert!(Rc::ptr_eq(&(&rc).lexiclean(), &rc));
+
+    let arc: Arc<Path> = Path::new("foo/bar").into();
+    assert!(Arc::ptr_eq(&(&arc).lexiclean(), &arc));
+
+    let boxed: Box<Path> = Path::new("foo/bar").into();
+    let ptr = boxed.as_os_str().as_encoded_bytes().as_ptr();
+    assert_eq!(
+      boxed.lexiclean().as_os_str().as_encoded_bytes().as_ptr(),
+      ptr
+    );
+  }
+
+  #[test]
+  fn strict_cleaning_rejects_escape() {
+    assert_eq!(
+      Path::new("/foo/../..").lexiclean_strict(),
+      Err(Error::Escape)
+    );
+    assert_eq!(Path::new("../foo").lexiclean_strict(), Ok("../foo".into()));
+  }
+
+  #[test]
+  fn trailing_slash_is_a_change() {
+    assert_eq!(
+      Path::new("foo/").lexiclean_changed(),
+      (PathBuf::from("foo"), true),
+    );
+  }
+
+  #[test]
+  fn clean_join_matches_join_then_clean() {
+    for (base, tail) in [
+      ("foo/bar", "../baz"),
+      ("foo", "../.."),
+      ("/foo", "../../bar"),
+      ("foo", "/bar/./baz"),
+      ("", "foo"),
+      ("foo", ""),
+      ("", ""),
+    ] {
+      assert_eq!(
+        clean_join(base, tail),
+        Path::new(base).join(tail).lexiclean(),
+      );
+    }
+  }
+
+  #[test]
+  fn relative_paths_are_absolutized_against_base() {
+    assert_eq!(
+      Path::new("a/../b").absolutize_with("/c/d"),
+      Path::new("/c/d/b")
+    );
+    assert_eq!(
+      Path::new("../../..").absolutize_with("/c/d"),
+      Path::new("/")
+    );
+    assert_eq!(Path::new("").absolutize_with("/c/./d/"), Path::new("/c/d"));
+  }
+
+  #[test]
+  fn absolute_paths_ignore_base() {
+    assert_eq!(Path::new("/a/./b").absolutize_with("/c"), Path::new("/a/b"));
+  }
+
+  #[test]
+  fn relative_paths_are_absolutized_against_current_dir() {
+    let current_dir = env::current_dir().unwrap();
+    assert_eq!(
+      Path::new("foo/../bar").lexiclean_absolute().unwrap(),
+      current_dir.join("bar").lexiclean(),
+    );
+  }
+
+  #[test]
+  fn absolute_paths_are_cleaned() {
+    let root = env::current_dir()
+      .unwrap()
+      .ancestors()
+      .last()
+      .unwrap()
+      .to_owned();
+    assert_eq!(
+      root.join("foo/./bar/..").lexiclean_absolute().unwrap(),
+      root.join("foo"),
+    );
+  }
+
+  #[test]
+  fn lexical_parent_understands_parent_dirs() {
+    assert_eq!(Path::new(".").parent_lexical(), Some("..".into()));
+    assert_eq!(Path::new("foo/..").parent_lexical(), Some("..".into()));
+    assert_eq!(Path::new("foo/bar/..").parent_lexical(), Some(".".into()));
+    assert_eq!(Path::new("../..").parent_lexical(), Some("../../..".into()));
+  }
+
+  #[test]
+  fn lexical_parent_of_normal_is_removed() {
+    assert_eq!(Path::new("foo").parent_lexical(), Some(".".into()));
+    assert_eq!(Path::new("foo/./bar/").parent_lexical(), Some("foo".into()));
+    assert_eq!(Path::new("/foo").parent_lexical(), Some("/".into()));
+    assert_eq!(Path::new("../foo").parent_lexical(), Some("..".into()));
+  }
+
+  #[test]
+  fn root_has_no_lexical_parent() {
+    assert_eq!(Path::new("/").parent_lexical(), None);
+    assert_eq!(Path::new("/foo/..").parent_lexical(), None);
+  }
+
+  #[test]
+  fn strings_can_be_cleaned() {
+    let string = "foo/./bar".to_string();
+    let os_string = OsString::from("foo/./bar");
+
+    assert_eq!("foo/./bar".lexiclean(), Path::new("foo/bar"));
+    assert_eq!((&string).lexiclean(), Path::new("foo/bar"));
+    assert_eq!(string.lexiclean(), Path::new("foo/bar"));
+    assert_eq!(OsStr::new("foo/./bar").lexiclean(), Path::new("foo/bar"));
+    assert_eq!((&os_string).lexiclean(), Path::new("foo/bar"));
+    assert_eq!(os_string.lexiclean(), Path::new("foo/bar"));
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn parent_dir_after_disk_is_removed() {
+    case(r"C:\..", r"C:\");
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn parent_dir_after_drive_relative_prefix_is_preserved() {
+    case(r"C:..", r"C:..");
+    case(r"C:..\..", r"C:..\..");
+    case(r"C:foo\..\..", r"C:..");
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn drive_relative_path_is_cleaned() {
+    case(r"C:foo\..\bar", r"C:bar");
+    case(r"C:foo/./bar", r"C:foo\bar");
+    case(r"C:foo\..", r"C:");
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn parent_dir_does_not_pop_unc_share() {
+    case(r"\\server\share\..", r"\\server\share\");
+    case(r"\\server\share\..\..", r"\\server\share\");
+    case(r"\\server\share\foo\..\..\bar", r"\\server\share\bar");
+    assert_eq!(Path::new(r"\\server\share\..").parent_lexical(), None);
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn parent_dir_does_not_pop_device() {
+    case(r"\\.\PhysicalDrive0", r"\\.\PhysicalDrive0");
+    case(r"\\.\pipe\name", r"\\.\pipe\name");
+    case(r"\\.\pipe\..", r"\\.\pipe\");
+    case(r"\\.\pipe\foo\..\..\bar", r"\\.\pipe\bar");
+    case(r"\\.\COM1\.", r"\\.\COM1\");
+    assert_eq!(Path::new(r"\\.\pipe\..").parent_lexical(), None);
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn verbatim_paths_are_not_cleaned() {
+    case(r"\\?\C:\foo\.\..\bar", r"\\?\C:\foo\.\..\bar");
+    case(r"\\?\UNC\server\share\..", r"\\?\UNC\server\share\..");
+    case(r"\\?\foo\..", r"\\?\foo\..");
+    assert_eq!(
+      Path::new(r"\\?\C:\foo\..").lexiclean().as_os_str(),
+      r"\\?\C:\foo\..",
+    );
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn lexical_parent_of_drive_relative_prefix() {
+    assert_eq!(Path::new(r"C:").parent_lexical(), Some(r"C:..".into()));
+    assert_eq!(Path::new(r"C:\").parent_lexical(), None);
+  }
+
+  #[test]
+  fn escape_depth() {
+    for (path, depth) in [
+      ("", 0),
+      ("a", 0),
+      ("a/..", 0),
+      ("..", 1),
+      ("../../a", 2),
+      ("a/../../..", 2),
+      ("../a/../..", 2),
+      ("./a/./../b/..", 0),
+      ("/..", 0),
+      ("/../../a", 0),
+    ] {
+      assert_eq!(Path::new(path).escape_depth(), depth, "{}", path);
+      assert_eq!(
+        Path::new(path).escape_depth(),
+        Path::new(path)
+          .lexiclean()
+          .components()
+          .take_while(|component| *component == Component::ParentDir)
+          .count(),
+        "{}",
+        path
+      );
+      assert_eq!(Path::new(path).escapes(), depth > 0, "{}", path);
+    }
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn escape_depth_of_prefixed_paths() {
+    assert_eq!(Path::new(r"C:..\..").escape_depth(), 2);
+    assert_eq!(Path::new(r"C:\..\..").escape_depth(), 0);
+    assert_eq!(Path::new(r"\\server\share\..").escape_depth(), 0);
+  }
+}
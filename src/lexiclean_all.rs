@@ -0,0 +1,69 @@
+use {
+  crate::{Lexiclean, Lexicleaner},
+  std::{
+    mem,
+    path::{Path, PathBuf},
+  },
+};
+
+/// Clean every path in `paths`, like `Lexiclean::lexiclean`, with a single
+/// cleaner shared by every path, allocating only the cleaned paths.
+///
+/// ```
+/// use lexiclean::lexiclean_all;
+/// use std::path::Path;
+///
+/// assert_eq!(
+///   lexiclean_all(&["foo/./bar", "foo/../baz", ""]),
+///   [Path::new("foo/bar"), Path::new("baz"), Path::new(".")],
+/// );
+/// ```
+pub fn lexiclean_all<P: AsRef<Path>>(paths: &[P]) -> Vec<PathBuf> {
+  let cleaner = Lexicleaner::new();
+  paths.iter().map(|path| cleaner.clean(path)).collect()
+}
+
+/// Clean every path in `paths` in place. On Unix, each path is cleaned in
+/// its own buffer, without allocating. Elsewhere, each path is cleaned into
+/// a scratch buffer, which is then swapped with it, so that apart from the
+/// first, every path is cleaned into a previous path's buffer.
+pub fn lexiclean_all_in_place(paths: &mut [PathBuf]) {
+  if cfg!(unix) {
+    for path in paths {
+      *path = mem::take(path).lexiclean();
+    }
+  } else {
+    let cleaner = Lexicleaner::new();
+    let mut scratch = PathBuf::new();
+    for path in paths {
+      cleaner.clean_into(&*path, &mut scratch);
+      mem::swap(path, &mut scratch);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PATHS: &[&str] = &["foo/./bar", "foo/../baz", "", "/..", "../foo/", "a//b/c/.."];
+
+  #[test]
+  fn paths_are_cleaned() {
+    assert_eq!(
+      lexiclean_all(PATHS),
+      PATHS
+        .iter()
+        .map(|path| path.lexiclean())
+        .collect::<Vec<PathBuf>>()
+    );
+    assert!(lexiclean_all::<&str>(&[]).is_empty());
+  }
+
+  #[test]
+  fn paths_are_cleaned_in_place() {
+    let mut paths = PATHS.iter().map(PathBuf::from).collect::<Vec<PathBuf>>();
+    lexiclean_all_in_place(&mut paths);
+    assert_eq!(paths, lexiclean_all(PATHS));
+  }
+}
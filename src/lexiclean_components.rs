@@ -0,0 +1,134 @@
+use {
+  crate::{is_root, is_verbatim},
+  std::path::{Component, Components},
+};
+
+/// Iterator over the components of a cleaned path, returned by
+/// `Lexiclean::lexiclean_components`.
+///
+/// Yields the same components as `path.lexiclean().components()`, without
+/// allocating. Each `Normal` component is checked against the remainder of
+/// the path to see if a later `..` removes it, so iteration is quadratic in
+/// the worst case.
+///
+/// Like `Lexiclean::lexiclean`, paths with a Windows verbatim prefix are not
+/// cleaned, and their components are yielded unchanged.
+#[derive(Clone, Debug)]
+pub struct LexicleanComponents<'a> {
+  components: Components<'a>,
+  empty: bool,
+  open: usize,
+  rooted: bool,
+  verbatim: bool,
+}
+
+impl<'a> LexicleanComponents<'a> {
+  pub(crate) fn new(components: Components<'a>) -> Self {
+    Self {
+      verbatim: is_verbatim(components.as_path()),
+      components,
+      empty: true,
+      open: 0,
+      rooted: false,
+    }
+  }
+
+  fn cancelled(&self) -> bool {
+    let mut depth = 0usize;
+
+    for component in self.components.clone() {
+      match component {
+        Component::Normal(_) => depth += 1,
+        Component::ParentDir => match depth.checked_sub(1) {
+          Some(remaining) => depth = remaining,
+          None => return true,
+        },
+        Component::CurDir | Component::Prefix(_) | Component::RootDir => {}
+      }
+    }
+
+    false
+  }
+}
+
+impl<'a> Iterator for LexicleanComponents<'a> {
+  type Item = Component<'a>;
+
+  fn next(&mut self) -> Option<Component<'a>> {
+    use Component::*;
+
+    if self.verbatim {
+      return self.components.next();
+    }
+
+    while let Some(component) = self.components.next() {
+      match component {
+        CurDir => continue,
+        ParentDir if self.open > 0 => {
+          self.open -= 1;
+          continue;
+        }
+        ParentDir if self.rooted => continue,
+        ParentDir => {}
+        Normal(_) => {
+          self.open += 1;
+          if self.cancelled() {
+            continue;
+          }
+        }
+        Prefix(_) | RootDir => {
+          if is_root(&component) {
+            self.rooted = true;
+          }
+        }
+      }
+
+      self.empty = false;
+      return Some(component);
+    }
+
+    if self.empty {
+      self.empty = false;
+      Some(CurDir)
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::Lexiclean;
+  use std::path::{Component, Path};
+
+  #[test]
+  fn normal_components_are_cancelled_by_later_parent_dirs() {
+    assert_eq!(
+      Path::new("a/b/c/../../d/..")
+        .lexiclean_components()
+        .collect::<Vec<Component>>(),
+      [Component::Normal("a".as_ref())],
+    );
+  }
+
+  #[test]
+  fn leading_parent_dirs_are_yielded() {
+    assert_eq!(
+      Path::new("a/../../b")
+        .lexiclean_components()
+        .filter(|component| *component == Component::ParentDir)
+        .count(),
+      1,
+    );
+  }
+
+  #[test]
+  fn empty_path_yields_current_dir() {
+    assert_eq!(
+      Path::new("a/..")
+        .lexiclean_components()
+        .collect::<Vec<Component>>(),
+      [Component::CurDir],
+    );
+  }
+}
@@ -0,0 +1,140 @@
+use {
+  crate::Lexiclean,
+  std::{
+    io::{self, BufRead, BufWriter, Write},
+    path::{Path, PathBuf},
+  },
+};
+
+/// Read paths separated by `delimiter` from `input`, clean them, and write
+/// them to `output`, each followed by `delimiter`. Paths are processed as
+/// they are read, and buffers are reused, so only reading and writing
+/// allocate. The last path need not be followed by `delimiter`.
+///
+/// `output` is buffered and flushed when `input` is exhausted. Paths are
+/// bytes on Unix, and must be valid UTF-8 elsewhere, failing with
+/// `io::ErrorKind::InvalidData` otherwise. Use `b'\n'` for paths read from
+/// text, and `b'\0'` for paths from `find -print0`.
+///
+/// ```
+/// use lexiclean::lexiclean_delimited;
+///
+/// let mut output = Vec::new();
+/// lexiclean_delimited(&b"foo/./bar\n/baz/..\n"[..], &mut output, b'\n').unwrap();
+/// assert_eq!(output, b"foo/bar\n/\n");
+/// ```
+pub fn lexiclean_delimited(
+  input: impl BufRead,
+  output: impl Write,
+  delimiter: u8,
+) -> io::Result<()> {
+  let mut output = BufWriter::new(output);
+  let mut cleaned = PathBuf::new();
+
+  for_each_delimited(input, delimiter, |path| {
+    path.lexiclean_into(&mut cleaned);
+    output.write_all(cleaned.as_os_str().as_encoded_bytes())?;
+    output.write_all(&[delimiter])
+  })?;
+
+  output.flush()
+}
+
+/// Read paths separated by `delimiter` from `input`, and call `f` with each
+/// one, stopping at the first error. This is the reading half of
+/// `lexiclean_delimited`, for callers which handle each path themselves.
+/// The read buffer is reused, so only reading allocates, and paths are
+/// converted from bytes like in `lexiclean_delimited`.
+///
+/// ```
+/// use lexiclean::for_each_delimited;
+/// use std::{io, path::PathBuf};
+///
+/// let mut paths = Vec::new();
+/// for_each_delimited(&b"foo\0bar"[..], b'\0', |path| {
+///   paths.push(path.to_owned());
+///   Ok::<(), io::Error>(())
+/// })
+/// .unwrap();
+/// assert_eq!(paths, [PathBuf::from("foo"), PathBuf::from("bar")]);
+/// ```
+pub fn for_each_delimited<E: From<io::Error>>(
+  mut input: impl BufRead,
+  delimiter: u8,
+  mut f: impl FnMut(&Path) -> Result<(), E>,
+) -> Result<(), E> {
+  let mut record = Vec::new();
+
+  loop {
+    record.clear();
+
+    if input.read_until(delimiter, &mut record)? == 0 {
+      return Ok(());
+    }
+
+    if record.last() == Some(&delimiter) {
+      record.pop();
+    }
+
+    f(path_from_bytes(&record)?)?;
+  }
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> io::Result<&Path> {
+  use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+  Ok(Path::new(OsStr::from_bytes(bytes)))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> io::Result<&Path> {
+  std::str::from_utf8(bytes)
+    .map(Path::new)
+    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[track_caller]
+  fn case(input: &[u8], delimiter: u8, want: &[u8]) {
+    let mut output = Vec::new();
+    lexiclean_delimited(input, &mut output, delimiter).unwrap();
+    assert_eq!(output, want);
+  }
+
+  #[test]
+  fn paths_are_cleaned() {
+    case(b"a/../b\n\nc//d/\n", b'\n', b"b\n.\nc/d\n");
+    case(b"a/..", b'\n', b".\n");
+    case(b"", b'\n', b"");
+  }
+
+  #[test]
+  fn delimiter_is_configurable() {
+    case(b"a\nb/.\0c/..\0", b'\0', b"a\nb\0.\0");
+    case(b"a/.:b/..:", b':', b"a:.:");
+  }
+
+  #[test]
+  fn errors_stop_iteration() {
+    let mut paths = Vec::new();
+    let result = for_each_delimited(&b"a\nb\nc\n"[..], b'\n', |path| {
+      paths.push(path.to_owned());
+      if paths.len() == 2 {
+        Err(io::Error::other("stop"))
+      } else {
+        Ok(())
+      }
+    });
+    assert_eq!(result.unwrap_err().to_string(), "stop");
+    assert_eq!(paths, [PathBuf::from("a"), PathBuf::from("b")]);
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn paths_need_not_be_unicode() {
+    case(b"\xff/./\xfe\n", b'\n', b"\xff/\xfe\n");
+  }
+}
@@ -0,0 +1,92 @@
+use {
+  crate::lexiclean_to_buf,
+  alloc::{alloc::Allocator, vec::Vec},
+};
+
+/// Clean the POSIX path `input`, like `lexiclean_to_buf`, into a vector
+/// allocated with `alloc`, such as a per-request arena, instead of the
+/// global allocator. `input` is treated as bytes, and need not be valid
+/// UTF-8, so on Unix, this cleans paths like `Lexiclean::lexiclean`.
+///
+/// The output is allocated once, with room for the cleaned path, and no
+/// other memory is allocated. Requires the `allocator_api` feature and a
+/// nightly toolchain.
+///
+/// ```
+/// #![feature(allocator_api)]
+///
+/// use {lexiclean::lexiclean_in, std::alloc::Global};
+///
+/// assert_eq!(lexiclean_in(b"/foo/./bar/../baz/", Global), b"/foo/baz");
+/// assert_eq!(lexiclean_in(b"", Global), b".");
+/// ```
+pub fn lexiclean_in<A: Allocator>(input: &[u8], alloc: A) -> Vec<u8, A> {
+  let mut out = Vec::with_capacity_in(input.len() + 1, alloc);
+  out.resize(input.len() + 1, 0);
+
+  let len = lexiclean_to_buf(input, &mut out)
+    .unwrap_or_else(|_| unreachable!("buffer one byte longer than input is too small"));
+
+  out.truncate(len);
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::lexiclean_posix,
+    alloc::alloc::{AllocError, Global, Layout},
+    core::{cell::Cell, ptr::NonNull},
+  };
+
+  /// Allocator which counts allocations, and forwards them to `Global`.
+  #[derive(Default)]
+  struct Counter {
+    allocations: Cell<usize>,
+  }
+
+  unsafe impl Allocator for &Counter {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+      self.allocations.set(self.allocations.get() + 1);
+      Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+      Global.deallocate(ptr, layout)
+    }
+  }
+
+  #[test]
+  fn paths_are_cleaned_like_lexiclean_posix() {
+    for path in [
+      "",
+      ".",
+      "/",
+      "//",
+      "..",
+      "/..",
+      "foo/./bar/..",
+      "a//b/../../../c",
+      "../a/",
+      "/a/b/c/.",
+    ] {
+      assert_eq!(
+        lexiclean_in(path.as_bytes(), Global),
+        lexiclean_posix(path).as_bytes(),
+        "{}",
+        path,
+      );
+    }
+
+    assert_eq!(lexiclean_in(b"\xFF/./\xC0/..", Global), b"\xFF");
+  }
+
+  #[test]
+  fn output_is_allocated_once_with_allocator() {
+    let counter = Counter::default();
+    let cleaned = lexiclean_in(b"/foo/./bar/../../../baz/qux/", &counter);
+    assert_eq!(cleaned, b"/baz/qux");
+    assert_eq!(counter.allocations.get(), 1);
+  }
+}
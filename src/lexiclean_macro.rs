@@ -0,0 +1,139 @@
+/// Clean a POSIX path string literal at compile time, like
+/// `lexiclean_posix`, producing a `&'static str`.
+///
+/// The argument may be any constant `&str` expression. Use `Path::new` to
+/// convert the result to a `&'static Path`.
+///
+/// ```
+/// use lexiclean::lexiclean;
+///
+/// const ASSETS: &str = lexiclean!("static/./css/../assets/");
+/// assert_eq!(ASSETS, "static/assets");
+///
+/// assert_eq!(lexiclean!(concat!("/srv/", "../www")), "/www");
+/// ```
+#[macro_export]
+macro_rules! lexiclean {
+  ($path:expr) => {{
+    const INPUT: &str = $path;
+    const CLEANED: ([u8; INPUT.len() + 1], usize) = $crate::__clean_const(INPUT.as_bytes());
+    const OUTPUT: &str = match ::core::str::from_utf8(CLEANED.0.split_at(CLEANED.1).0) {
+      Ok(output) => output,
+      Err(_) => panic!("cleaned path is not valid UTF-8"),
+    };
+    OUTPUT
+  }};
+}
+
+/// Clean the POSIX path `input` into an array, returning the array and the
+/// length of the cleaned path, for `lexiclean!`. This is the algorithm of
+/// `lexiclean_to_buf`, restricted to what is possible in a `const fn`. `N`
+/// must be greater than the length of `input`.
+#[doc(hidden)]
+pub const fn __clean_const<const N: usize>(input: &[u8]) -> ([u8; N], usize) {
+  let mut out = [0; N];
+
+  let rooted = !input.is_empty() && input[0] == b'/';
+
+  let mut len = 0;
+
+  if rooted {
+    out[0] = b'/';
+    len = 1;
+  }
+
+  let mut normals = 0;
+  let mut start = 0;
+
+  while start < input.len() {
+    let mut end = start;
+    while end < input.len() && input[end] != b'/' {
+      end += 1;
+    }
+
+    let dot = end - start == 1 && input[start] == b'.';
+    let dot_dot = end - start == 2 && input[start] == b'.' && input[start + 1] == b'.';
+
+    if end == start || dot || (dot_dot && normals == 0 && rooted) {
+    } else if dot_dot && normals > 0 {
+      // Remove the last component, and the separator before it, unless it
+      // is the root.
+      let mut i = len;
+      while i > 0 && out[i - 1] != b'/' {
+        i -= 1;
+      }
+      len = if i == 0 {
+        0
+      } else if i == 1 && rooted {
+        1
+      } else {
+        i - 1
+      };
+      normals -= 1;
+    } else {
+      if !dot_dot {
+        normals += 1;
+      }
+
+      if len > rooted as usize {
+        out[len] = b'/';
+        len += 1;
+      }
+
+      let mut i = start;
+      while i < end {
+        out[len] = input[i];
+        len += 1;
+        i += 1;
+      }
+    }
+
+    start = end + 1;
+  }
+
+  if len == 0 {
+    out[0] = b'.';
+    len = 1;
+  }
+
+  (out, len)
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::lexiclean_posix,
+    alloc::{format, string::String, vec},
+  };
+
+  #[test]
+  fn literals_are_cleaned_at_compile_time() {
+    const CLEANED: &str = lexiclean!("foo/./bar/../baz/");
+    assert_eq!(CLEANED, "foo/baz");
+    assert_eq!(lexiclean!(""), ".");
+    assert_eq!(lexiclean!("/.."), "/");
+    assert_eq!(lexiclean!("../ünï/./cödé"), "../ünï/cödé");
+  }
+
+  #[test]
+  fn paths_are_cleaned_like_lexiclean_posix() {
+    let mut paths = vec![String::new()];
+
+    for _ in 0..6 {
+      paths = paths
+        .iter()
+        .flat_map(|path| {
+          ["a", ".", "/"]
+            .iter()
+            .map(move |c| format!("{}{}", path, c))
+        })
+        .collect();
+
+      for path in &paths {
+        let (out, len) = __clean_const::<7>(path.as_bytes());
+        assert_eq!(&out[..len], lexiclean_posix(path).as_bytes(), "{}", path);
+      }
+    }
+  }
+}
@@ -0,0 +1,200 @@
+use core::{
+  convert::TryInto,
+  fmt::{self, Display, Formatter},
+};
+
+/// Error returned by `lexiclean_to_buf` if the output buffer is too small.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BufferTooSmall;
+
+impl Display for BufferTooSmall {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "output buffer is too small")
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BufferTooSmall {}
+
+/// Clean the POSIX path `input` into `out`, like `lexiclean_posix`, without
+/// allocating, returning the length of the cleaned path, for environments
+/// where allocation is impossible, like signal handlers and embedded
+/// targets. `input` is treated as bytes, and need not be valid UTF-8.
+///
+/// Components are written to `out` as they are kept, before a later `..`
+/// may remove them, and fails with `BufferTooSmall` if `out` fills up.
+/// Cleaning never writes past the length of `input`, except for the `.`
+/// that an empty path is cleaned to, so a buffer one byte longer than
+/// `input` is always large enough. On failure, the contents of `out` are
+/// unspecified.
+///
+/// ```
+/// use lexiclean::{lexiclean_to_buf, BufferTooSmall};
+///
+/// let mut out = [0; 16];
+/// let len = lexiclean_to_buf(b"/foo/./bar/../baz/", &mut out).unwrap();
+/// assert_eq!(&out[..len], b"/foo/baz");
+///
+/// assert_eq!(lexiclean_to_buf(b"foo/bar", &mut [0; 4]), Err(BufferTooSmall));
+/// ```
+pub fn lexiclean_to_buf(input: &[u8], out: &mut [u8]) -> Result<usize, BufferTooSmall> {
+  let rooted = input.first() == Some(&b'/');
+
+  let mut len = 0;
+
+  if rooted {
+    *out.first_mut().ok_or(BufferTooSmall)? = b'/';
+    len = 1;
+  }
+
+  let mut normals = 0;
+  let mut start = 0;
+
+  while start < input.len() {
+    let end = find_separator(&input[start..]).map_or(input.len(), |i| start + i);
+
+    match &input[start..end] {
+      b"" | b"." => {}
+      b".." if normals > 0 => {
+        len = match out[..len].iter().rposition(|&byte| byte == b'/') {
+          Some(0) if rooted => 1,
+          Some(i) => i,
+          None => 0,
+        };
+        normals -= 1;
+      }
+      b".." if rooted => {}
+      component => {
+        if component != b".." {
+          normals += 1;
+        }
+
+        let separator = usize::from(len > usize::from(rooted));
+
+        let dst = out
+          .get_mut(len..len + separator + component.len())
+          .ok_or(BufferTooSmall)?;
+
+        if separator == 1 {
+          dst[0] = b'/';
+        }
+
+        dst[separator..].copy_from_slice(component);
+        len += separator + component.len();
+      }
+    }
+
+    start = end + 1;
+  }
+
+  if len == 0 {
+    *out.first_mut().ok_or(BufferTooSmall)? = b'.';
+    len = 1;
+  }
+
+  Ok(len)
+}
+
+/// Find the first `/` in `bytes`, checking eight bytes at a time, for paths
+/// with long components.
+pub(crate) fn find_separator(bytes: &[u8]) -> Option<usize> {
+  const LO: u64 = u64::from_ne_bytes([0x01; 8]);
+  const HI: u64 = u64::from_ne_bytes([0x80; 8]);
+  const SEPARATORS: u64 = u64::from_ne_bytes([b'/'; 8]);
+
+  let mut chunks = bytes.chunks_exact(8);
+  let mut offset = 0;
+
+  for chunk in &mut chunks {
+    // Bytes which are `/` are zero in `x`, and the lowest byte with its high
+    // bit set in `zeros` is the first zero byte of `x`, since false positives
+    // only occur above zero bytes.
+    let x = u64::from_le_bytes(chunk.try_into().unwrap()) ^ SEPARATORS;
+    let zeros = x.wrapping_sub(LO) & !x & HI;
+    if zeros != 0 {
+      return Some(offset + zeros.trailing_zeros() as usize / 8);
+    }
+    offset += 8;
+  }
+
+  chunks
+    .remainder()
+    .iter()
+    .position(|&byte| byte == b'/')
+    .map(|i| offset + i)
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::lexiclean_posix,
+    alloc::{string::String, vec},
+  };
+
+  #[test]
+  fn separators_are_found() {
+    for len in 0..20 {
+      for separator in 0..=len {
+        let mut bytes = vec![b'a'; len];
+        if separator < len {
+          bytes[separator] = b'/';
+        }
+        bytes.extend_from_slice(b"/.//");
+        assert_eq!(find_separator(&bytes), Some(separator));
+        assert_eq!(
+          find_separator(&bytes[..len]),
+          bytes[..len].iter().position(|&b| b == b'/')
+        );
+      }
+    }
+
+    assert_eq!(find_separator("\u{2f2f}/".as_bytes()), Some(3));
+    assert_eq!(find_separator(&[0x2e, 0x30, 0xaf, 0x2f]), Some(3));
+  }
+
+  #[test]
+  fn paths_are_cleaned_like_lexiclean_posix() {
+    let mut paths = vec![String::new()];
+
+    for _ in 0..6 {
+      paths = paths
+        .iter()
+        .flat_map(|path| {
+          ["a", ".", "/"]
+            .iter()
+            .map(move |c| format!("{}{}", path, c))
+        })
+        .collect();
+
+      for path in &paths {
+        let want = lexiclean_posix(path);
+
+        let mut out = vec![0; path.len() + 1];
+        let len = lexiclean_to_buf(path.as_bytes(), &mut out).unwrap();
+        assert_eq!(&out[..len], want.as_bytes(), "{}", path);
+
+        assert_eq!(
+          lexiclean_to_buf(path.as_bytes(), &mut out[..want.len() - 1]),
+          Err(BufferTooSmall),
+          "{}",
+          path
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn buffer_may_be_shorter_than_input() {
+    let mut out = [0; 3];
+    let len = lexiclean_to_buf(b"a/./b/../c/", &mut out).unwrap();
+    assert_eq!(&out[..len], b"a/c");
+
+    assert_eq!(lexiclean_to_buf(b"", &mut []), Err(BufferTooSmall));
+    assert_eq!(lexiclean_to_buf(b"/", &mut []), Err(BufferTooSmall));
+    assert_eq!(
+      lexiclean_to_buf(b"foo/..", &mut [0; 1]),
+      Err(BufferTooSmall)
+    );
+  }
+}
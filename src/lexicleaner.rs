@@ -0,0 +1,992 @@
+use {
+  crate::{is_root, is_verbatim, win32, CleanError, DriveLetterCase, Error, Separator},
+  std::{
+    ffi::{OsStr, OsString},
+    mem,
+    path::{self, Component, Path, PathBuf, Prefix, MAIN_SEPARATOR_STR},
+  },
+};
+
+/// Configurable lexical path cleaner.
+///
+/// `Lexicleaner::new()` cleans paths exactly like `Lexiclean::lexiclean`.
+/// Builder methods adjust that behavior, and may be combined freely:
+///
+/// ```
+/// use lexiclean::Lexicleaner;
+/// use std::path::Path;
+///
+/// let cleaner = Lexicleaner::new().deny_escape(true);
+///
+/// assert_eq!(cleaner.clean("/foo/../.."), Path::new("/"));
+/// assert!(cleaner.try_clean("/foo/../..").is_err());
+/// ```
+///
+/// Options that reject paths are only enforced by `try_clean` and
+/// `try_clean_into`. `clean` and `clean_into` never fail.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Lexicleaner {
+  clean_verbatim: bool,
+  deny_control_characters: bool,
+  deny_escape: bool,
+  deny_reserved_names: bool,
+  deny_streams: bool,
+  drive_letter_case: Option<DriveLetterCase>,
+  #[cfg(feature = "case-fold")]
+  fold_case: bool,
+  leading_current_dir: bool,
+  max_components: Option<usize>,
+  #[cfg(feature = "unicode-normalization")]
+  normalize_unicode: Option<crate::Form>,
+  preserve_parent_dirs: bool,
+  preserve_parent_of_root: bool,
+  preserve_trailing_separator: bool,
+  separator: Option<Separator>,
+  simplify_verbatim: bool,
+  strip_trailing_dots_and_spaces: bool,
+  verbatim_if_long: bool,
+}
+
+impl Lexicleaner {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Clean paths with a Windows verbatim prefix, like `\\?\C:\foo`.
+  ///
+  /// Windows does not normalize verbatim paths, so `.` and `..` are ordinary
+  /// names in them, and by default, verbatim paths are passed through
+  /// unchanged, and are not checked by `try_clean`. Only `drive_letter_case`
+  /// applies to them.
+  pub fn clean_verbatim(mut self, clean_verbatim: bool) -> Self {
+    self.clean_verbatim = clean_verbatim;
+    self
+  }
+
+  /// Reject paths with components containing a NUL byte, with `Error::Nul`,
+  /// or another C0 or C1 control character, with `Error::ControlCharacter`,
+  /// since such paths are almost always attacks or corruption when they
+  /// arrive over the network. Components removed by a later `..` are also
+  /// checked.
+  pub fn deny_control_characters(mut self, deny_control_characters: bool) -> Self {
+    self.deny_control_characters = deny_control_characters;
+    self
+  }
+
+  /// Reject paths in which a `..` would ascend above the root, instead of
+  /// dropping the `..`. Takes precedence over `preserve_parent_of_root`.
+  pub fn deny_escape(mut self, deny_escape: bool) -> Self {
+    self.deny_escape = deny_escape;
+    self
+  }
+
+  /// Reject paths with components that are Windows reserved device names,
+  /// as determined by `is_reserved_name`, on every platform, so that paths
+  /// can be refused before writing files that cannot be opened on Windows.
+  pub fn deny_reserved_names(mut self, deny_reserved_names: bool) -> Self {
+    self.deny_reserved_names = deny_reserved_names;
+    self
+  }
+
+  /// Reject paths with components containing `:`, which Windows interprets
+  /// as NTFS alternate data stream syntax, like `file.txt:stream`. Drive
+  /// prefixes, like `C:`, are not rejected. Use this to flag unexpected
+  /// streams in untrusted input.
+  pub fn deny_streams(mut self, deny_streams: bool) -> Self {
+    self.deny_streams = deny_streams;
+    self
+  }
+
+  /// Normalize the case of Windows drive letters, in both `C:` and `\\?\C:`
+  /// prefixes, so that cleaned paths can be used as map keys. Has no effect
+  /// on platforms without drive letters.
+  pub fn drive_letter_case(mut self, drive_letter_case: DriveLetterCase) -> Self {
+    self.drive_letter_case = Some(drive_letter_case);
+    self
+  }
+
+  /// Lowercase `Normal` components, producing a key for comparing paths on
+  /// case-insensitive filesystems. Characters are lowercased individually
+  /// with `char::to_lowercase`, which approximates Unicode simple case
+  /// folding. Components that are not valid Unicode are left unchanged.
+  #[cfg(feature = "case-fold")]
+  pub fn fold_case(mut self, fold_case: bool) -> Self {
+    self.fold_case = fold_case;
+    self
+  }
+
+  /// Prefix relative paths that do not start with `..` with `./`, so that
+  /// they are not subject to `PATH` lookup when executed, and cannot be
+  /// mistaken for options if they start with `-`.
+  pub fn leading_current_dir(mut self, leading_current_dir: bool) -> Self {
+    self.leading_current_dir = leading_current_dir;
+    self
+  }
+
+  /// Reject paths with more than `max_components` components, before
+  /// cleaning, so that work and memory are bounded for untrusted input.
+  pub fn max_components(mut self, max_components: usize) -> Self {
+    self.max_components = Some(max_components);
+    self
+  }
+
+  /// Apply Unicode normalization form `form` to `Normal` components, so that
+  /// paths which look the same compare equal, for example names stored in
+  /// NFD by macOS and the same names in NFC. This is an aid for comparing
+  /// and storing paths, since filesystems may treat differently normalized
+  /// names as different files. Components that are not valid Unicode are
+  /// left unchanged. If combined with `fold_case`, case is folded first.
+  #[cfg(feature = "unicode-normalization")]
+  pub fn normalize_unicode(mut self, form: crate::Form) -> Self {
+    self.normalize_unicode = Some(form);
+    self
+  }
+
+  /// Do not remove `..` components along with the preceding component, since
+  /// `foo/..` only refers to `.` if `foo` is not a symlink. Only `.`
+  /// components, repeated separators, and trailing separators are removed.
+  ///
+  /// `..` components directly following the root are still subject to
+  /// `preserve_parent_of_root` and `deny_escape`.
+  pub fn preserve_parent_dirs(mut self, preserve_parent_dirs: bool) -> Self {
+    self.preserve_parent_dirs = preserve_parent_dirs;
+    self
+  }
+
+  /// Keep `..` components that would ascend above the root, so that `/..` is
+  /// cleaned to `/..` instead of `/`. Use this when the path will be
+  /// interpreted by a system with different root semantics, or to detect
+  /// such paths after cleaning.
+  pub fn preserve_parent_of_root(mut self, preserve_parent_of_root: bool) -> Self {
+    self.preserve_parent_of_root = preserve_parent_of_root;
+    self
+  }
+
+  /// Keep a single trailing separator, if the input has one, to preserve the
+  /// intent that the path names a directory. `foo//` is cleaned to `foo/`.
+  pub fn preserve_trailing_separator(mut self, preserve_trailing_separator: bool) -> Self {
+    self.preserve_trailing_separator = preserve_trailing_separator;
+    self
+  }
+
+  /// Use `separator` for every separator in cleaned paths, instead of the
+  /// mixture of `/` and `\` in the input, for stable display and string
+  /// comparison. Has no effect on platforms where `\` is not a separator, or
+  /// on verbatim paths, in which `/` is not a separator.
+  pub fn separator(mut self, separator: Separator) -> Self {
+    self.separator = Some(separator);
+    self
+  }
+
+  /// Remove verbatim prefixes when doing so does not change which file a
+  /// path refers to, converting `\\?\C:\foo` to `C:\foo`, and
+  /// `\\?\UNC\server\share` to `\\server\share`, so that paths are
+  /// presentable to users and usable by legacy tools. The resulting path is
+  /// then cleaned.
+  ///
+  /// Paths are only converted if they are shorter than `MAX_PATH`, and none
+  /// of their components are `.` or `..`, end with a dot or space, contain
+  /// characters that are invalid without a verbatim prefix, or are reserved
+  /// device names.
+  pub fn simplify_verbatim(mut self, simplify_verbatim: bool) -> Self {
+    self.simplify_verbatim = simplify_verbatim;
+    self
+  }
+
+  /// Remove trailing dots and spaces from each component, as Win32 does when
+  /// opening a path, so that cleaned paths predict which file will actually
+  /// be opened. `foo.\bar ` is cleaned to `foo\bar`. Components consisting
+  /// only of dots and spaces, other than `.` and `..`, are removed.
+  pub fn strip_trailing_dots_and_spaces(mut self, strip_trailing_dots_and_spaces: bool) -> Self {
+    self.strip_trailing_dots_and_spaces = strip_trailing_dots_and_spaces;
+    self
+  }
+
+  /// Add a verbatim prefix to cleaned absolute paths that are too long to be
+  /// used without one, converting `C:\foo` to `\\?\C:\foo`, and
+  /// `\\server\share` to `\\?\UNC\server\share`. Paths shorter than
+  /// `MAX_PATH`, and paths whose meaning would change with a verbatim prefix,
+  /// for example because a component ends with a dot, are left unchanged.
+  pub fn verbatim_if_long(mut self, verbatim_if_long: bool) -> Self {
+    self.verbatim_if_long = verbatim_if_long;
+    self
+  }
+
+  pub fn clean(&self, path: impl AsRef<Path>) -> PathBuf {
+    let mut out = PathBuf::new();
+    self.clean_into(path, &mut out);
+    out
+  }
+
+  /// Clean `path` into `out`, replacing its contents but reusing its
+  /// allocation.
+  pub fn clean_into(&self, path: impl AsRef<Path>, out: &mut PathBuf) {
+    self
+      .clean_path(path.as_ref(), false, out)
+      .unwrap_or_else(|(_, error)| unreachable!("unchecked cleaning failed: {}", error));
+  }
+
+  pub fn try_clean(&self, path: impl AsRef<Path>) -> Result<PathBuf, Error> {
+    let mut out = PathBuf::new();
+    self.try_clean_into(path, &mut out)?;
+    Ok(out)
+  }
+
+  /// Clean `path` into `out`, replacing its contents but reusing its
+  /// allocation. `out` is left unchanged if cleaning fails.
+  pub fn try_clean_into(&self, path: impl AsRef<Path>, out: &mut PathBuf) -> Result<(), Error> {
+    self
+      .clean_path(path.as_ref(), true, out)
+      .map_err(|(_, error)| error)
+  }
+
+  /// Like `try_clean`, but on failure, returns a `CleanError`, which names
+  /// the offending component, and its index and byte offset in `path`.
+  pub fn try_clean_detailed(&self, path: impl AsRef<Path>) -> Result<PathBuf, CleanError> {
+    let path = path.as_ref();
+    let mut out = PathBuf::new();
+    self
+      .clean_path(path, true, &mut out)
+      .map_err(|(index, error)| CleanError::new(path, index, error))?;
+    Ok(out)
+  }
+
+  /// Errors are returned with the index of the offending component.
+  fn clean_path(
+    &self,
+    path: &Path,
+    checked: bool,
+    out: &mut PathBuf,
+  ) -> Result<(), (usize, Error)> {
+    // With the default options, which cannot fail, Unix paths are cleaned
+    // as bytes.
+    #[cfg(unix)]
+    if *self == Self::new() {
+      crate::unix::clean_into(path, out);
+      return Ok(());
+    }
+
+    if self.simplify_verbatim {
+      if let Some(simplified) = win32::from_verbatim(path) {
+        return self.clean_path(&simplified, checked, out);
+      }
+    }
+
+    if !self.clean_verbatim && is_verbatim(path) {
+      self.pass_through_verbatim(path, out);
+      return Ok(());
+    }
+
+    // Cleaned components are written directly into `out`, so if cleaning can
+    // fail, the path is checked first, to leave `out` unchanged on failure.
+    if checked
+      && (self.deny_control_characters
+        || self.deny_escape
+        || self.deny_reserved_names
+        || self.deny_streams
+        || self.max_components.is_some())
+    {
+      self.clean_components_into(path.components(), true, &mut ())?;
+    }
+
+    // The cleaned path is never longer than `path`, except that an empty
+    // path is cleaned to `.`, and `leading_current_dir` can add `./`.
+    out.clear();
+    out.reserve(path.as_os_str().len() + 2);
+
+    self.write_components(path.components(), out);
+
+    if self.preserve_trailing_separator
+      && has_trailing_separator(path)
+      && !has_trailing_separator(out)
+    {
+      out.as_mut_os_string().push(MAIN_SEPARATOR_STR);
+    }
+
+    if let Some(separator) = self.separator {
+      if path::is_separator('\\') && !is_verbatim(out) {
+        let mut bytes = mem::take(out).into_os_string().into_encoded_bytes();
+        for byte in &mut bytes {
+          if path::is_separator((*byte).into()) {
+            *byte = separator.byte();
+          }
+        }
+        // SAFETY: Only ASCII separators were replaced, with other ASCII
+        // separators, and `bytes` came from `OsString::into_encoded_bytes`.
+        *out = unsafe { OsString::from_encoded_bytes_unchecked(bytes) }.into();
+      }
+    }
+
+    if self.verbatim_if_long && win32::is_long(out) {
+      if let Some(verbatim) = win32::to_verbatim(&*out) {
+        *out = verbatim;
+      }
+    }
+
+    Ok(())
+  }
+
+  fn pass_through_verbatim(&self, path: &Path, out: &mut PathBuf) {
+    out.clear();
+
+    let path = path.as_os_str();
+
+    if let (Some(case), Some(Component::Prefix(prefix))) =
+      (self.drive_letter_case, Path::new(path).components().next())
+    {
+      if let Prefix::VerbatimDisk(letter) = prefix.kind() {
+        let bytes = path.as_encoded_bytes();
+        // SAFETY: The rest of the path follows the `:` of the prefix, which
+        // is ASCII, and `bytes` came from `OsStr::as_encoded_bytes`.
+        let rest =
+          unsafe { OsStr::from_encoded_bytes_unchecked(&bytes[prefix.as_os_str().len()..]) };
+        let out = out.as_mut_os_string();
+        out.push(format!(r"\\?\{}:", case.apply(letter)));
+        out.push(rest);
+        return;
+      }
+    }
+
+    // Pushing onto a verbatim `PathBuf` normalizes the pushed path, so the
+    // path is appended to the underlying `OsString` instead.
+    out.as_mut_os_string().push(path);
+  }
+
+  pub(crate) fn clean_components<'a>(
+    &self,
+    components: impl IntoIterator<Item = Component<'a>>,
+    checked: bool,
+  ) -> Result<Vec<Component<'a>>, Error> {
+    let mut cleaned = Vec::new();
+    self
+      .clean_components_into(components, checked, &mut cleaned)
+      .map_err(|(_, error)| error)?;
+    Ok(cleaned)
+  }
+
+  /// Clean `components` directly into `out`, without checking them.
+  pub(crate) fn write_components<'a>(
+    &self,
+    components: impl IntoIterator<Item = Component<'a>>,
+    out: &mut PathBuf,
+  ) {
+    out.clear();
+    self
+      .clean_components_into(
+        components,
+        false,
+        &mut Writer {
+          cleaner: self,
+          len: 0,
+          out,
+        },
+      )
+      .unwrap_or_else(|(_, error)| unreachable!("unchecked cleaning failed: {}", error));
+  }
+
+  /// Clean `components` into `sink`, in a single pass. Only the number of
+  /// `Normal` components which a `..` could remove, and the last component
+  /// before them, are tracked, since removing a component never depends on
+  /// any earlier ones. Errors are returned with the index of the offending
+  /// component.
+  fn clean_components_into<'a>(
+    &self,
+    components: impl IntoIterator<Item = Component<'a>>,
+    checked: bool,
+    sink: &mut impl Sink<'a>,
+  ) -> Result<(), (usize, Error)> {
+    use Component::*;
+
+    let mut components = components.into_iter().peekable();
+
+    if let Some(Prefix(prefix)) = components.peek() {
+      if prefix.kind().is_verbatim() && !self.clean_verbatim {
+        for component in components {
+          sink.push(component);
+        }
+        return Ok(());
+      }
+    }
+
+    let mut anchor = None;
+    let mut len = 0;
+    let mut normals = 0;
+
+    for (i, component) in components.enumerate() {
+      if let Some(max) = self.max_components {
+        if checked && i == max {
+          return Err((i, Error::TooManyComponents { max }));
+        }
+      }
+
+      let component = match component {
+        Normal(name) if self.strip_trailing_dots_and_spaces => {
+          let bytes = name.as_encoded_bytes();
+          let len = bytes
+            .iter()
+            .rposition(|&byte| byte != b'.' && byte != b' ')
+            .map_or(0, |i| i + 1);
+          // SAFETY: `bytes` is only truncated before ASCII dots and spaces,
+          // and came from `OsStr::as_encoded_bytes`.
+          Normal(unsafe { OsStr::from_encoded_bytes_unchecked(&bytes[..len]) })
+        }
+        _ => component,
+      };
+
+      match component {
+        CurDir => {}
+        Normal(name) if name.is_empty() => {}
+        ParentDir if normals > 0 && !self.preserve_parent_dirs => {
+          sink.pop();
+          len -= 1;
+          normals -= 1;
+        }
+        ParentDir if normals == 0 && anchor.as_ref().is_some_and(is_root) => {
+          if checked && self.deny_escape {
+            return Err((i, Error::Escape));
+          }
+
+          if self.preserve_parent_of_root {
+            sink.push(component);
+            len += 1;
+            anchor = Some(component);
+          }
+        }
+        Normal(name) if checked && self.deny_control_characters && has_control_character(name) => {
+          let error = if name.as_encoded_bytes().contains(&0) {
+            Error::Nul
+          } else {
+            Error::ControlCharacter
+          };
+          return Err((i, error));
+        }
+        Normal(name) if checked && self.deny_reserved_names && win32::is_reserved_name(name) => {
+          return Err((i, Error::ReservedName))
+        }
+        Normal(name) if checked && self.deny_streams && name.as_encoded_bytes().contains(&b':') => {
+          return Err((i, Error::Stream))
+        }
+        Normal(_) => {
+          sink.push(component);
+          len += 1;
+          normals += 1;
+        }
+        ParentDir | Prefix(_) | RootDir => {
+          sink.push(component);
+          len += 1;
+          anchor = Some(component);
+          normals = 0;
+        }
+      }
+    }
+
+    if len == 0 {
+      sink.push(CurDir);
+    }
+
+    Ok(())
+  }
+}
+
+/// Destination of cleaned components. Only the most recently pushed
+/// component is ever popped, and only if it is `Normal`.
+trait Sink<'a> {
+  fn push(&mut self, component: Component<'a>);
+
+  fn pop(&mut self);
+}
+
+/// Discards components, for checking a path without cleaning it.
+impl<'a> Sink<'a> for () {
+  fn push(&mut self, _: Component<'a>) {}
+
+  fn pop(&mut self) {}
+}
+
+impl<'a> Sink<'a> for Vec<Component<'a>> {
+  fn push(&mut self, component: Component<'a>) {
+    Vec::push(self, component);
+  }
+
+  fn pop(&mut self) {
+    Vec::pop(self);
+  }
+}
+
+/// Renders components into a `PathBuf`, applying the cleaner's rendering
+/// options.
+struct Writer<'o> {
+  cleaner: &'o Lexicleaner,
+  len: usize,
+  out: &'o mut PathBuf,
+}
+
+impl<'a> Sink<'a> for Writer<'_> {
+  fn push(&mut self, component: Component<'a>) {
+    // A leading `.` added by `leading_current_dir` is not counted, and is
+    // replaced if every component after it is removed.
+    if self.len == 0 {
+      self.out.clear();
+      if self.cleaner.leading_current_dir {
+        if let Component::Normal(_) = component {
+          self.out.push(Component::CurDir);
+        }
+      }
+    }
+
+    self.len += 1;
+
+    match component {
+      Component::Prefix(prefix) => match (self.cleaner.drive_letter_case, prefix.kind()) {
+        (Some(case), Prefix::Disk(letter)) => self.out.push(format!("{}:", case.apply(letter))),
+        (Some(case), Prefix::VerbatimDisk(letter)) => {
+          self.out.push(format!(r"\\?\{}:", case.apply(letter)))
+        }
+        _ => self.out.push(component),
+      },
+      Component::Normal(normal) => self.push_normal(normal),
+      _ => self.out.push(component),
+    }
+  }
+
+  fn pop(&mut self) {
+    self.out.pop();
+    self.len -= 1;
+  }
+}
+
+impl Writer<'_> {
+  fn push_normal(&mut self, normal: &OsStr) {
+    #[cfg(any(feature = "case-fold", feature = "unicode-normalization"))]
+    if let Some(normal) = normal.to_str() {
+      let mut normal = std::borrow::Cow::Borrowed(normal);
+
+      #[cfg(feature = "case-fold")]
+      if self.cleaner.fold_case {
+        normal = normal.chars().flat_map(char::to_lowercase).collect();
+      }
+
+      #[cfg(feature = "unicode-normalization")]
+      if let Some(form) = self.cleaner.normalize_unicode {
+        normal = form.apply(&normal).into();
+      }
+
+      self.out.push(&*normal);
+      return;
+    }
+
+    self.out.push(normal);
+  }
+}
+
+fn has_trailing_separator(path: &Path) -> bool {
+  path
+    .as_os_str()
+    .as_encoded_bytes()
+    .last()
+    .is_some_and(|&byte| path::is_separator(byte.into()))
+}
+
+/// Returns `true` if `name` contains a C0 control character, `DEL`, or a C1
+/// control character, which is encoded as `0xC2` followed by a byte in
+/// `0x80..=0x9F`.
+pub(crate) fn has_control_character(name: &OsStr) -> bool {
+  let bytes = name.as_encoded_bytes();
+  bytes.iter().any(u8::is_ascii_control)
+    || bytes
+      .windows(2)
+      .any(|pair| pair[0] == 0xC2 && (0x80..=0x9F).contains(&pair[1]))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn default_matches_lexiclean() {
+    use crate::Lexiclean;
+
+    for path in ["", ".", "foo/..", "/foo/../../bar", "../foo/./bar/"] {
+      assert_eq!(Lexicleaner::new().clean(path), Path::new(path).lexiclean());
+    }
+  }
+
+  #[test]
+  fn escape_is_dropped_by_default() {
+    assert_eq!(
+      Lexicleaner::new().try_clean("/foo/../.."),
+      Ok(PathBuf::from("/"))
+    );
+  }
+
+  #[test]
+  fn escape_is_denied() {
+    let cleaner = Lexicleaner::new().deny_escape(true);
+    assert_eq!(cleaner.try_clean("/.."), Err(Error::Escape));
+    assert_eq!(cleaner.try_clean("/foo/../.."), Err(Error::Escape));
+    assert_eq!(cleaner.try_clean("/foo/.."), Ok(PathBuf::from("/")));
+    assert_eq!(cleaner.try_clean("../.."), Ok(PathBuf::from("../..")));
+  }
+
+  #[test]
+  fn failed_try_clean_into_leaves_out_unchanged() {
+    let mut out = PathBuf::from("foo");
+    let result = Lexicleaner::new()
+      .deny_escape(true)
+      .try_clean_into("/..", &mut out);
+    assert_eq!(result, Err(Error::Escape));
+    assert_eq!(out, Path::new("foo"));
+  }
+
+  #[test]
+  fn trailing_separator_is_preserved() {
+    let cleaner = Lexicleaner::new().preserve_trailing_separator(true);
+
+    for (path, want) in [
+      ("foo/", "foo/"),
+      ("foo//", "foo/"),
+      ("foo/./", "foo/"),
+      ("foo", "foo"),
+      ("foo/bar/../", "foo/"),
+      ("foo/../", "./"),
+      ("../", "../"),
+      ("/", "/"),
+      ("/foo/../", "/"),
+    ] {
+      assert_eq!(
+        cleaner.clean(path).as_os_str(),
+        want.replace('/', MAIN_SEPARATOR_STR).as_str(),
+        "{}",
+        path
+      );
+    }
+  }
+
+  #[test]
+  fn trailing_separator_is_removed_by_default() {
+    assert_eq!(Lexicleaner::new().clean("foo/").as_os_str(), "foo");
+  }
+
+  #[test]
+  fn leading_current_dir_is_added() {
+    let cleaner = Lexicleaner::new().leading_current_dir(true);
+
+    for (path, want) in [
+      ("foo", "./foo"),
+      ("./foo/", "./foo"),
+      ("-foo", "./-foo"),
+      ("foo/..", "."),
+      ("foo/../..", ".."),
+      ("foo/../bar", "./bar"),
+      ("", "."),
+      ("../foo", "../foo"),
+      ("/foo", "/foo"),
+    ] {
+      assert_eq!(
+        cleaner.clean(path).as_os_str(),
+        want.replace('/', MAIN_SEPARATOR_STR).as_str(),
+        "{}",
+        path
+      );
+    }
+  }
+
+  #[test]
+  fn leading_current_dir_and_trailing_separator_can_be_combined() {
+    assert_eq!(
+      Lexicleaner::new()
+        .leading_current_dir(true)
+        .preserve_trailing_separator(true)
+        .clean("foo/bar/../")
+        .as_os_str(),
+      "./foo/".replace('/', MAIN_SEPARATOR_STR).as_str(),
+    );
+  }
+
+  #[test]
+  fn parent_of_root_is_preserved() {
+    let cleaner = Lexicleaner::new().preserve_parent_of_root(true);
+    assert_eq!(cleaner.clean("/.."), Path::new("/.."));
+    assert_eq!(cleaner.clean("/foo/../.."), Path::new("/.."));
+    assert_eq!(cleaner.clean("/../foo/.."), Path::new("/.."));
+    assert_eq!(cleaner.clean("/../../foo"), Path::new("/../../foo"));
+    assert_eq!(cleaner.clean("/foo/.."), Path::new("/"));
+  }
+
+  #[test]
+  fn escape_is_denied_even_if_parent_of_root_is_preserved() {
+    let cleaner = Lexicleaner::new()
+      .preserve_parent_of_root(true)
+      .deny_escape(true);
+    assert_eq!(cleaner.try_clean("/.."), Err(Error::Escape));
+    assert_eq!(cleaner.clean("/.."), Path::new("/.."));
+  }
+
+  #[test]
+  fn parent_dirs_are_preserved() {
+    let cleaner = Lexicleaner::new().preserve_parent_dirs(true);
+
+    for (path, want) in [
+      ("foo/..", "foo/.."),
+      ("./foo//./bar/../", "foo/bar/.."),
+      ("../foo/..", "../foo/.."),
+      ("/../foo/..", "/foo/.."),
+    ] {
+      assert_eq!(
+        cleaner.clean(path).as_os_str(),
+        want.replace('/', MAIN_SEPARATOR_STR).as_str(),
+        "{}",
+        path
+      );
+    }
+  }
+
+  #[test]
+  fn component_count_is_limited() {
+    let cleaner = Lexicleaner::new().max_components(3);
+    assert_eq!(cleaner.try_clean("a/b/c"), Ok("a/b/c".into()));
+    assert_eq!(cleaner.try_clean("/a/b"), Ok("/a/b".into()));
+    assert_eq!(
+      cleaner.try_clean("a/../b/.."),
+      Err(Error::TooManyComponents { max: 3 })
+    );
+    assert_eq!(cleaner.clean("a/b/c/d"), Path::new("a/b/c/d"));
+  }
+
+  #[test]
+  #[cfg(feature = "case-fold")]
+  fn case_is_folded() {
+    let cleaner = Lexicleaner::new().fold_case(true);
+    assert_eq!(cleaner.clean("FOO/./Bar/../BAZ"), Path::new("foo/baz"));
+    assert_eq!(cleaner.clean("/ΑΒΓ/Straße"), Path::new("/αβγ/straße"));
+  }
+
+  #[test]
+  #[cfg(all(feature = "case-fold", unix))]
+  fn non_unicode_components_are_not_folded() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let path = Path::new(OsStr::from_bytes(b"FOO/\xFF/BAR"));
+    assert_eq!(
+      Lexicleaner::new().fold_case(true).clean(path),
+      Path::new(OsStr::from_bytes(b"foo/\xFF/bar")),
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "unicode-normalization")]
+  fn unicode_is_normalized() {
+    use crate::Form;
+
+    let nfc = "caf\u{e9}/r\u{e9}sum\u{e9}";
+    let nfd = "cafe\u{301}/re\u{301}sume\u{301}";
+
+    let cleaner = Lexicleaner::new().normalize_unicode(Form::Nfc);
+    assert_eq!(cleaner.clean(nfd), Path::new(nfc));
+    assert_eq!(cleaner.clean(format!("{}/./x/..", nfc)), Path::new(nfc));
+
+    let cleaner = Lexicleaner::new().normalize_unicode(Form::Nfd);
+    assert_eq!(cleaner.clean(nfc), Path::new(nfd));
+
+    assert_eq!(
+      Lexicleaner::new()
+        .normalize_unicode(Form::Nfkc)
+        .clean("\u{fb01}le"),
+      Path::new("file"),
+    );
+    assert_eq!(
+      Lexicleaner::new()
+        .normalize_unicode(Form::Nfkd)
+        .clean("\u{2460}"),
+      Path::new("1"),
+    );
+  }
+
+  #[test]
+  #[cfg(all(feature = "case-fold", feature = "unicode-normalization"))]
+  fn case_is_folded_before_normalization() {
+    let cleaner = Lexicleaner::new()
+      .fold_case(true)
+      .normalize_unicode(crate::Form::Nfc);
+    assert_eq!(cleaner.clean("CAFE\u{301}"), Path::new("caf\u{e9}"));
+  }
+
+  #[test]
+  #[cfg(all(feature = "unicode-normalization", unix))]
+  fn non_unicode_components_are_not_normalized() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let path = Path::new(OsStr::from_bytes(b"e\xCC\x81/\xFF"));
+    assert_eq!(
+      Lexicleaner::new()
+        .normalize_unicode(crate::Form::Nfc)
+        .clean(path),
+      Path::new(OsStr::from_bytes(b"\xC3\xA9/\xFF")),
+    );
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn drive_letter_case_is_normalized() {
+    let upper = Lexicleaner::new().drive_letter_case(DriveLetterCase::Upper);
+    assert_eq!(upper.clean(r"c:\foo\..\bar").as_os_str(), r"C:\bar");
+    assert_eq!(upper.clean(r"c:foo").as_os_str(), r"C:foo");
+    assert_eq!(upper.clean(r"\\?\c:\foo").as_os_str(), r"\\?\C:\foo");
+    assert_eq!(
+      upper.clean(r"\\server\share\foo").as_os_str(),
+      r"\\server\share\foo"
+    );
+
+    let lower = Lexicleaner::new().drive_letter_case(DriveLetterCase::Lower);
+    assert_eq!(lower.clean(r"C:\foo").as_os_str(), r"c:\foo");
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn verbatim_paths_are_passed_through() {
+    let cleaner = Lexicleaner::new().deny_escape(true).max_components(1);
+    assert_eq!(
+      cleaner.try_clean(r"\\?\C:\foo\..\..").unwrap().as_os_str(),
+      r"\\?\C:\foo\..\..",
+    );
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn verbatim_paths_can_be_cleaned() {
+    let cleaner = Lexicleaner::new().clean_verbatim(true);
+    assert_eq!(
+      cleaner.clean(r"\\?\C:\foo\.\..\bar").as_os_str(),
+      r"\\?\C:\bar",
+    );
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn verbatim_paths_are_simplified() {
+    let cleaner = Lexicleaner::new().simplify_verbatim(true);
+    assert_eq!(cleaner.clean(r"\\?\C:\foo\bar").as_os_str(), r"C:\foo\bar");
+    assert_eq!(
+      cleaner.clean(r"\\?\UNC\server\share\foo").as_os_str(),
+      r"\\server\share\foo",
+    );
+    assert_eq!(
+      cleaner.clean(r"\\?\C:\foo\..\bar").as_os_str(),
+      r"\\?\C:\foo\..\bar",
+    );
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn long_paths_are_made_verbatim() {
+    let cleaner = Lexicleaner::new().verbatim_if_long(true);
+
+    let long = "a".repeat(300);
+    assert_eq!(
+      cleaner.clean(format!(r"C:\foo\..\{}", long)).as_os_str(),
+      &*format!(r"\\?\C:\{}", long),
+    );
+    assert_eq!(
+      cleaner
+        .clean(format!(r"\\server\share\{}", long))
+        .as_os_str(),
+      &*format!(r"\\?\UNC\server\share\{}", long),
+    );
+    assert_eq!(cleaner.clean(r"C:\foo\..\bar").as_os_str(), r"C:\bar");
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn separators_are_normalized() {
+    let slash = Lexicleaner::new().separator(Separator::Slash);
+    assert_eq!(slash.clean(r"C:\foo/bar\..\baz").as_os_str(), "C:/foo/baz");
+    assert_eq!(
+      slash.clean(r"\\server\share\foo").as_os_str(),
+      "//server/share/foo"
+    );
+    assert_eq!(slash.clean(r"\\?\C:\foo").as_os_str(), r"\\?\C:\foo");
+
+    let backslash = Lexicleaner::new().separator(Separator::Backslash);
+    assert_eq!(backslash.clean("C:/foo/bar").as_os_str(), r"C:\foo\bar");
+    assert_eq!(
+      backslash.clean("//server/share/foo").as_os_str(),
+      r"\\server\share\foo"
+    );
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn separator_has_no_effect_on_unix() {
+    let cleaner = Lexicleaner::new().separator(Separator::Backslash);
+    assert_eq!(cleaner.clean("foo/./bar"), Path::new("foo/bar"));
+    assert_eq!(cleaner.clean(r"foo\bar"), Path::new(r"foo\bar"));
+  }
+
+  #[test]
+  fn streams_are_denied() {
+    let cleaner = Lexicleaner::new().deny_streams(true);
+    assert_eq!(cleaner.try_clean("foo/file.txt:stream"), Err(Error::Stream));
+    assert_eq!(cleaner.try_clean("foo:bar/baz"), Err(Error::Stream));
+    assert_eq!(cleaner.try_clean("foo/./bar"), Ok("foo/bar".into()));
+    assert_eq!(
+      cleaner.clean("foo/file.txt:stream"),
+      Path::new("foo/file.txt:stream")
+    );
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn drive_prefixes_are_not_streams() {
+    let cleaner = Lexicleaner::new().deny_streams(true);
+    assert_eq!(cleaner.try_clean(r"C:\foo"), Ok(r"C:\foo".into()));
+    assert_eq!(cleaner.try_clean(r"C:foo"), Ok(r"C:foo".into()));
+    assert_eq!(cleaner.try_clean(r"C:\foo:bar"), Err(Error::Stream));
+  }
+
+  #[test]
+  fn trailing_dots_and_spaces_are_stripped() {
+    let cleaner = Lexicleaner::new().strip_trailing_dots_and_spaces(true);
+    assert_eq!(cleaner.clean("foo./bar "), Path::new("foo/bar"));
+    assert_eq!(cleaner.clean("foo. . /bar"), Path::new("foo/bar"));
+    assert_eq!(cleaner.clean("foo/.../bar"), Path::new("foo/bar"));
+    assert_eq!(cleaner.clean("foo../.."), Path::new("."));
+    assert_eq!(cleaner.clean(".foo ./bar"), Path::new(".foo/bar"));
+    assert_eq!(cleaner.clean("../foo"), Path::new("../foo"));
+    assert_eq!(
+      Lexicleaner::new().clean("foo./bar "),
+      Path::new("foo./bar ")
+    );
+  }
+
+  #[test]
+  fn control_characters_are_denied() {
+    let cleaner = Lexicleaner::new().deny_control_characters(true);
+    assert_eq!(cleaner.try_clean("foo/bar\0"), Err(Error::Nul));
+    assert_eq!(cleaner.try_clean("foo\nbar"), Err(Error::ControlCharacter));
+    assert_eq!(
+      cleaner.try_clean("foo\x1b[0m"),
+      Err(Error::ControlCharacter)
+    );
+    assert_eq!(cleaner.try_clean("foo\u{7f}"), Err(Error::ControlCharacter));
+    assert_eq!(cleaner.try_clean("foo\u{85}"), Err(Error::ControlCharacter));
+    assert_eq!(cleaner.try_clean("foo\n/.."), Err(Error::ControlCharacter));
+    assert_eq!(cleaner.try_clean("föö/Ä"), Ok("föö/Ä".into()));
+    assert_eq!(cleaner.clean("foo\nbar"), Path::new("foo\nbar"));
+  }
+
+  #[test]
+  fn reserved_names_are_denied() {
+    let cleaner = Lexicleaner::new().deny_reserved_names(true);
+    assert_eq!(cleaner.try_clean("foo/nul.txt"), Err(Error::ReservedName));
+    assert_eq!(cleaner.try_clean("COM1/foo"), Err(Error::ReservedName));
+    assert_eq!(cleaner.try_clean("foo/console"), Ok("foo/console".into()));
+    assert_eq!(cleaner.clean("foo/nul.txt"), Path::new("foo/nul.txt"));
+  }
+
+  #[test]
+  fn clean_into_replaces_contents() {
+    let mut out = PathBuf::from("foo");
+    Lexicleaner::new().clean_into("bar/baz/..", &mut out);
+    assert_eq!(out, Path::new("bar"));
+  }
+}
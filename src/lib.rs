@@ -1,5 +1,6 @@
-//! This crate exports a single trait, `Lexiclean`, with a single method,
-//! `lexiclean`, implemented on `&Path`, that performs lexical path cleaning.
+//! This crate exports a single trait, `Lexiclean`, implemented on `&Path`,
+//! `PathBuf`, and string types, whose `lexiclean` method performs lexical path
+//! cleaning.
 //!
 //! Lexical path cleaning simplifies paths without looking at the underlying
 //! filesystem. This means:
@@ -17,154 +18,297 @@
 //!
 //! - Lexiclean does not respect symlinks.
 //!
+//! - A Windows UNC share, like `\\server\share`, is part of the root, so
+//!   `\\server\share\..` resolves to `\\server\share\`, and not `\\server`,
+//!   which cannot be opened. Likewise, the device of a Windows DOS device
+//!   path, like `\\.\pipe`, is part of the root, so `\\.\pipe\..` resolves to
+//!   `\\.\pipe\`.
+//!
+//! - Paths with a Windows verbatim prefix, like `\\?\C:\`, are returned
+//!   unchanged, since Windows treats `.` and `..` in them as ordinary names.
+//!
+//! Cleaning a `PathBuf`, `String`, or `OsString` reuses its buffer. On Unix,
+//! the path is cleaned in place, without allocating. `Lexiclean::lexiclean_into`
+//! writes the cleaned path into an existing `PathBuf`, reusing its allocation.
+//! Cleaning keeps a fixed amount of state no matter how many components a
+//! path has, so deep paths never allocate any more than shallow ones.
+//! `lexiclean_all` and `lexiclean_all_in_place` clean many paths at once,
+//! and `lexiclean_delimited` cleans a stream of delimited paths, like the
+//! output of `find -print0`, which `for_each_delimited` reads without
+//! cleaning. With the `rayon` feature, `par_lexiclean_all` and
+//! `ParLexicleaned::par_lexicleaned` clean many paths in parallel.
+//!
+//! `absolute` makes paths absolute like `std::path::absolute`, for older
+//! toolchains, and `Lexiclean::lexiclean_absolute` also cleans them.
+//!
+//! `Box<Path>`, `Rc<Path>`, and `Arc<Path>` clean to the same kind of smart
+//! pointer. If the path is already clean, the original allocation is returned.
+//! With the `camino` feature, `Utf8Path` and `Utf8PathBuf` clean to
+//! `Utf8PathBuf`.
+//!
+//! `Lexicleaner` is a builder for cleaning with non-default behavior. With
+//! the `unicode-normalization` feature, `Lexicleaner::normalize_unicode`
+//! applies a Unicode normalization `Form` to components, so that names
+//! stored in NFD, like on macOS, compare equal to the same names in NFC.
+//!
+//! `CleanPathBuf` is an owned path which is kept clean as it is modified.
+//! `RelativePathBuf` and `AbsolutePathBuf` are also guaranteed to be
+//! relative and absolute. With the `serde` feature, they implement
+//! `Serialize` and `Deserialize`, and deserialization cleans and validates
+//! them like their constructors, and the `serde` module has helpers for
+//! cleaning `PathBuf` fields the same way. With the `clap` feature,
+//! `CleanPathValueParser` parses command-line arguments into them, and is
+//! used by `clap::value_parser!` for them.
+//!
+//! `LexicalKey` compares and hashes paths by their cleaned forms, for use as
+//! map keys, and with the `case-fold` feature, `LexicalKey::case_insensitive`
+//! also folds their case. `cmp_lexical` compares paths by their cleaned
+//! components.
+//!
+//! `Jail` confines untrusted relative paths to a root directory, and
+//! `resolve_under_root` resolves HTTP request paths under a document root.
+//! With the `percent-decode` feature, `resolve_encoded_under_root` also
+//! percent-decodes them first. `to_git_path` converts relative paths to the
+//! form Git expects.
+//!
+//! `PurePosixPath` and `PureWindowsPath` parse, join, clean, and render paths
+//! of a specific flavor, regardless of the host platform, and
+//! `lexiclean_posix` and `lexiclean_windows` clean strings as POSIX and
+//! Windows paths. With the `typed-path` feature, `LexicleanTyped` cleans the
+//! UTF-8 paths of the `typed-path` crate the same way.
+//! `PurePath::lexiclean_compat` cleans paths exactly like the path cleaning
+//! functions of other languages, selected with `Compat`.
+//! `to_cygwin`, `to_msys`, and `from_cygwin` convert between Windows paths and
+//! Cygwin and MSYS paths, and `to_wsl` and `from_wsl` between Windows paths
+//! and WSL paths. With the `file-url` feature, `to_file_url` and
+//! `from_file_url` convert between paths and `file:` URLs.
+//! `remove_dot_segments` removes `.` and `..` segments from URL paths, as
+//! specified by RFC 3986.
+//!
+//! `realpath_logical` and `try_realpath_logical` resolve paths against a
+//! current directory like GNU `realpath --logical --canonicalize-missing`.
+//!
+//! `DriveDirectories` resolves Windows paths, including drive-relative paths
+//! like `C:foo`, against caller-supplied current directories, on any
+//! platform.
+//!
+//! `split_stream` and `join_stream` separate NTFS alternate data stream
+//! suffixes, like `:Zone.Identifier`, from paths, and reattach them.
+//! `to_verbatim` and `from_verbatim` convert between Windows paths with and
+//! without verbatim prefixes, like `\\?\UNC\server\share` and
+//! `\\server\share`.
+//!
+//! The cleaning algorithm is also available as `clean_components`, which
+//! accepts any iterator of `Component`s, and `clean_join`, which joins and
+//! cleans two paths in one pass.
+//!
+//! Everything that uses `std::path` requires the default `std` feature.
+//! Without it, the crate is `no_std`, and requires only `alloc`. `PurePath`,
+//! `DriveDirectories`, `lexiclean_posix`, `lexiclean_windows`, and
+//! `remove_dot_segments` are still available, and work with paths stored as
+//! strings. `lexiclean_to_buf` cleans POSIX paths stored as bytes into a
+//! fixed buffer, without allocating, and the `lexiclean!` macro cleans POSIX
+//! path literals at compile time. With the `allocator_api` feature, on a
+//! nightly toolchain, `lexiclean_in` cleans them into a vector allocated with
+//! a caller-provided allocator. On other toolchains, the feature is ignored.
+//!
+//! With the `cli` feature, the `lexiclean` binary cleans paths given as
+//! arguments or read from standard input. Run `lexiclean --help` for usage.
+//!
+//! With the `ffi` feature, the `ffi` module exports C functions for cleaning
+//! paths, declared in `include/lexiclean.h`, which are included in static
+//! and dynamic libraries built from this crate, for example with:
+//!
+//! ```sh
+//! cargo rustc --lib --features ffi --crate-type staticlib
+//! ```
+//!
+//! With the `wasm` feature, the `wasm` module exports `lexiclean_posix` and
+//! `lexiclean_windows` to JavaScript with `wasm-bindgen`, as `lexicleanPosix`
+//! and `lexicleanWindows`, for example with:
+//!
+//! ```sh
+//! cargo rustc --lib --features wasm --target wasm32-unknown-unknown --crate-type cdylib
+//! ```
+//!
+//! With the `python` feature, the `python` module is a Python extension
+//! module named `lexiclean`, built with PyO3, for example with:
+//!
+//! ```sh
+//! cargo rustc --lib --features python,pyo3/extension-module --crate-type cdylib
+//! ```
+//!
+//! With the `test-support` feature, `model_lexiclean` is a simple reference
+//! implementation of POSIX path cleaning, for differential testing, and the
+//! `strategy` module has `proptest` strategies that generate adversarial
+//! paths.
+//!
 //! Additional test cases and bug fixes are most welcome!
-use std::path::{Component, Path, PathBuf};
-
-pub trait Lexiclean {
-  fn lexiclean(self) -> PathBuf;
-}
-
-impl Lexiclean for &Path {
-  fn lexiclean(self) -> PathBuf {
-    use Component::*;
-
-    let mut components = Vec::new();
-
-    for component in self.components() {
-      match component {
-        CurDir => {}
-        ParentDir => match components.last() {
-          Some(Normal(_)) => {
-            components.pop();
-          }
-          Some(ParentDir) | None => components.push(component),
-          Some(RootDir) | Some(Prefix(_)) => {}
-          Some(CurDir) => unreachable!(),
-        },
-        Normal(_) | Prefix(_) | RootDir => components.push(component),
-      }
-    }
-
-    if components.is_empty() {
-      components.push(CurDir);
-    }
-
-    components.into_iter().collect()
-  }
-}
-
-#[cfg(test)]
-mod tests {
-  use super::*;
-
-  #[track_caller]
-  fn case(path: &str, want: &str) {
-    assert_eq!(Path::new(path).lexiclean(), Path::new(want));
-  }
-
-  #[test]
-  fn empty_path_maps_to_current_dir() {
-    case("", ".");
-  }
-
-  #[test]
-  fn single_current_dir_is_preserved() {
-    case(".", ".");
-  }
-
-  #[test]
-  fn multiple_current_dirs_are_collapsed() {
-    case("././.", ".");
-  }
-
-  #[test]
-  fn leading_parent_dir_is_preserved() {
-    case("..", "..");
-  }
-
-  #[test]
-  fn multiple_parent_dirs_are_preserved() {
-    case("../../..", "../../..");
-  }
-
-  #[test]
-  fn trailing_slash_is_removed() {
-    case("foo/", "foo");
-  }
-
-  #[test]
-  fn leading_current_dir_is_removed() {
-    case("./foo", "foo");
-  }
-
-  #[test]
-  fn trailing_parent_dir_after_current_dir_is_preserved() {
-    case("./..", "..");
-  }
+#![cfg_attr(all(feature = "allocator_api", nightly), feature(allocator_api))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-  #[test]
-  fn trailing_current_dir_is_removed() {
-    case("foo/.", "foo");
-  }
+extern crate alloc;
 
-  #[test]
-  fn intermediate_current_dir_is_removed() {
-    case("foo/./bar", "foo/bar");
-  }
+pub use crate::{
+  drive_directories::DriveDirectories,
+  error::Error,
+  lexiclean_macro::__clean_const,
+  lexiclean_to_buf::{lexiclean_to_buf, BufferTooSmall},
+  pure_path::{
+    lexiclean_posix, lexiclean_windows, Flavor, Posix, PureComponent, PurePath, PurePosixPath,
+    PureWindowsPath, Windows,
+  },
+  remove_dot_segments::remove_dot_segments,
+};
 
-  #[test]
-  fn multiple_current_dirs_are_removed() {
-    case("././.", ".");
-  }
+#[cfg(feature = "std")]
+pub use crate::{
+  absolute::absolute,
+  absolute_path_buf::AbsolutePathBuf,
+  access_policy::AccessPolicy,
+  ancestors_lexical::AncestorsLexical,
+  clean_error::CleanError,
+  clean_path_buf::CleanPathBuf,
+  compat::Compat,
+  cygwin::{from_cygwin, to_cygwin, to_msys},
+  drive_letter_case::DriveLetterCase,
+  git::to_git_path,
+  jail::{resolve_under_root, Jail},
+  lexical_key::{cmp_lexical, LexicalKey},
+  lexiclean::{clean_components, clean_join, Lexiclean},
+  lexiclean_all::{lexiclean_all, lexiclean_all_in_place},
+  lexiclean_components::LexicleanComponents,
+  lexiclean_delimited::{for_each_delimited, lexiclean_delimited},
+  lexicleaner::Lexicleaner,
+  path_lint::{lint_path, PathLint},
+  path_stack::PathStack,
+  realpath::{realpath_logical, try_realpath_logical},
+  relative::common_ancestor,
+  relative_path_buf::RelativePathBuf,
+  sanitize_entry::{sanitize_entry, EntryError},
+  sanitize_filename::{sanitize_filename, Replacement},
+  separator::Separator,
+  stream::{join_stream, split_stream},
+  win32::{from_verbatim, is_reserved_name, to_verbatim},
+  wsl::{from_wsl, to_wsl},
+};
 
-  #[test]
-  fn parent_dir_after_root_is_removed() {
-    case("/..", "/");
-  }
+#[cfg(all(feature = "allocator_api", nightly))]
+pub use crate::lexiclean_in::lexiclean_in;
 
-  #[test]
-  fn current_dir_after_root_is_removed() {
-    case("/.", "/");
-  }
+#[cfg(feature = "clap")]
+pub use crate::clap::CleanPathValueParser;
 
-  #[test]
-  fn multiple_slashes_are_removed() {
-    case("//foo//bar//", "/foo/bar");
-  }
+#[cfg(feature = "file-url")]
+pub use crate::file_url::{from_file_url, to_file_url};
 
-  #[test]
-  fn normal_after_root_is_preserved() {
-    case("/foo", "/foo");
-  }
+#[cfg(feature = "percent-decode")]
+pub use crate::percent_decode::{percent_decode_path, resolve_encoded_under_root};
 
-  #[test]
-  fn intermediate_parent_dir_is_removed() {
-    case("/foo/../bar", "/bar");
-  }
+#[cfg(feature = "rayon")]
+pub use crate::rayon::{par_lexiclean_all, ParLexicleaned};
 
-  #[test]
-  fn trailing_parent_dir_pops_normal() {
-    case("/foo/bar/..", "/foo");
-  }
+#[cfg(feature = "test-support")]
+pub use crate::model::model_lexiclean;
 
-  #[test]
-  fn trailing_parent_dir_pops_normal_before_current() {
-    case("/foo/bar/./..", "/foo");
-  }
+#[cfg(feature = "typed-path")]
+pub use crate::typed_path::LexicleanTyped;
 
-  #[test]
-  fn normal_is_preserved() {
-    case("foo", "foo");
-  }
+#[cfg(feature = "unicode-normalization")]
+pub use crate::form::Form;
 
-  #[test]
-  fn parent_dir_after_normal_is_current() {
-    case("foo/..", ".");
-  }
+#[cfg(feature = "std")]
+use crate::lexiclean::{check_nul, is_root, is_verbatim};
 
-  #[test]
-  #[cfg(windows)]
-  fn parent_dir_after_disk_is_removed() {
-    case(r"C:\..", r"C:\");
-  }
-}
+#[cfg(feature = "std")]
+mod absolute;
+#[cfg(feature = "std")]
+mod absolute_path_buf;
+#[cfg(feature = "std")]
+mod access_policy;
+#[cfg(feature = "std")]
+mod ancestors_lexical;
+#[cfg(feature = "camino")]
+mod camino;
+#[cfg(feature = "clap")]
+mod clap;
+#[cfg(feature = "std")]
+mod clean_error;
+#[cfg(feature = "std")]
+mod clean_path_buf;
+#[cfg(feature = "std")]
+mod compat;
+#[cfg(feature = "std")]
+mod cygwin;
+mod drive_directories;
+#[cfg(feature = "std")]
+mod drive_letter_case;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "file-url")]
+mod file_url;
+#[cfg(feature = "unicode-normalization")]
+mod form;
+#[cfg(feature = "std")]
+mod git;
+#[cfg(feature = "std")]
+mod jail;
+#[cfg(feature = "std")]
+mod lexical_key;
+#[cfg(feature = "std")]
+mod lexiclean;
+#[cfg(feature = "std")]
+mod lexiclean_all;
+#[cfg(feature = "std")]
+mod lexiclean_components;
+#[cfg(feature = "std")]
+mod lexiclean_delimited;
+#[cfg(all(feature = "allocator_api", nightly))]
+mod lexiclean_in;
+mod lexiclean_macro;
+mod lexiclean_to_buf;
+#[cfg(feature = "std")]
+mod lexicleaner;
+#[cfg(feature = "test-support")]
+mod model;
+#[cfg(feature = "std")]
+mod path_lint;
+#[cfg(feature = "std")]
+mod path_stack;
+#[cfg(feature = "percent-decode")]
+mod percent_decode;
+mod pure_path;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "rayon")]
+mod rayon;
+#[cfg(feature = "std")]
+mod realpath;
+#[cfg(feature = "std")]
+mod relative;
+#[cfg(feature = "std")]
+mod relative_path_buf;
+mod remove_dot_segments;
+#[cfg(feature = "std")]
+mod sanitize_entry;
+#[cfg(feature = "std")]
+mod sanitize_filename;
+#[cfg(feature = "std")]
+mod separator;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "test-support")]
+pub mod strategy;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "typed-path")]
+mod typed_path;
+#[cfg(all(feature = "std", unix))]
+mod unix;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+mod win32;
+#[cfg(feature = "std")]
+mod wsl;
@@ -0,0 +1,107 @@
+use alloc::{format, string::String, vec::Vec};
+
+/// A deliberately simple reference implementation of POSIX path cleaning,
+/// for differential testing of wrappers and reimplementations, available
+/// with the `test-support` feature. Its output is identical to that of
+/// `lexiclean_posix`, and on Unix, `Lexiclean::lexiclean`, but it makes no
+/// attempt to be fast.
+///
+/// ```
+/// use lexiclean::{lexiclean_posix, model_lexiclean};
+///
+/// for path in ["", "foo/../..", "/../foo//bar/."] {
+///   assert_eq!(model_lexiclean(path), lexiclean_posix(path));
+/// }
+/// ```
+pub fn model_lexiclean(path: &str) -> String {
+  let rooted = path.starts_with('/');
+
+  let mut names: Vec<&str> = Vec::new();
+
+  for name in path.split('/') {
+    if name.is_empty() || name == "." {
+      continue;
+    }
+
+    if name != ".." {
+      names.push(name);
+    } else if names.last().is_some_and(|&last| last != "..") {
+      names.pop();
+    } else if !rooted {
+      names.push(name);
+    }
+  }
+
+  let joined = names.join("/");
+
+  if rooted {
+    format!("/{}", joined)
+  } else if joined.is_empty() {
+    ".".into()
+  } else {
+    joined
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::{lexiclean_posix, strategy::posix_path},
+    proptest::prelude::*,
+  };
+
+  fn paths(len: usize) -> Vec<String> {
+    let mut paths = vec![String::new()];
+
+    for _ in 0..len {
+      let mut longer = Vec::new();
+      for path in &paths {
+        for c in ['a', '.', '/'] {
+          longer.push(format!("{}{}", path, c));
+        }
+      }
+      paths.extend(longer);
+    }
+
+    paths.sort();
+    paths.dedup();
+    paths
+  }
+
+  #[test]
+  fn model_matches_implementations() {
+    for path in paths(6) {
+      let model = model_lexiclean(&path);
+
+      assert_eq!(model, lexiclean_posix(&path), "{:?}", path);
+
+      #[cfg(all(unix, feature = "std"))]
+      {
+        use {crate::Lexiclean, std::path::Path};
+        assert_eq!(
+          Path::new(&path).lexiclean().as_os_str(),
+          model.as_str(),
+          "{:?}",
+          path
+        );
+      }
+    }
+  }
+
+  proptest! {
+    #[test]
+    fn model_matches_implementations_on_adversarial_paths(path in posix_path()) {
+      let model = model_lexiclean(&path);
+
+      prop_assert_eq!(&model, &lexiclean_posix(&path));
+
+      #[cfg(all(unix, feature = "std"))]
+      {
+        use {crate::Lexiclean, std::path::Path};
+        let cleaned = Path::new(&path).lexiclean();
+        prop_assert_eq!(cleaned.as_os_str(), model.as_str());
+      }
+    }
+  }
+}
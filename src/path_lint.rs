@@ -0,0 +1,197 @@
+use {
+  crate::{is_reserved_name, is_root, lexicleaner::has_control_character, Lexiclean},
+  std::{
+    fmt::{self, Display, Formatter},
+    path::{Component, Path},
+  },
+};
+
+/// The length, in bytes, above which a component is too long for most file
+/// systems.
+const MAX_COMPONENT_LEN: usize = 255;
+
+/// A reason a path is suspicious, returned by `lint_path`.
+///
+/// Warnings about a single component carry the index of that component in
+/// `Path::components`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathLint {
+  /// A component contains a C0 or C1 control character, like a newline.
+  ControlCharacter { component: usize },
+  /// A `..` component would ascend above the start or root of the path.
+  Escape { component: usize },
+  /// A component is longer than 255 bytes.
+  LongComponent { component: usize, len: usize },
+  /// The path contains both `/` and `\`.
+  MixedSeparators,
+  /// The path is not clean, so `lexiclean` would change it.
+  NotClean,
+  /// A component is a Windows reserved device name, as determined by
+  /// `is_reserved_name`.
+  ReservedName { component: usize },
+  /// A component ends with a dot or space, which Windows removes.
+  TrailingDotOrSpace { component: usize },
+}
+
+impl Display for PathLint {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::ControlCharacter { component } => {
+        write!(f, "component {} contains a control character", component)
+      }
+      Self::Escape { component } => {
+        write!(
+          f,
+          "component {} ascends above the start of the path",
+          component
+        )
+      }
+      Self::LongComponent { component, len } => write!(
+        f,
+        "component {} is {} bytes long, more than {}",
+        component, len, MAX_COMPONENT_LEN
+      ),
+      Self::MixedSeparators => write!(f, "path contains both `/` and `\\`"),
+      Self::NotClean => write!(f, "path is not clean"),
+      Self::ReservedName { component } => {
+        write!(f, "component {} is a reserved device name", component)
+      }
+      Self::TrailingDotOrSpace { component } => {
+        write!(f, "component {} ends with a dot or space", component)
+      }
+    }
+  }
+}
+
+/// Inspect `path` and return the reasons it is suspicious, without changing
+/// it, so tools can tell users why a path will be rejected or rewritten.
+/// Warnings about the path as a whole come first, followed by warnings about
+/// each component, in order.
+///
+/// ```
+/// use lexiclean::{lint_path, PathLint};
+///
+/// assert_eq!(lint_path("foo/bar"), []);
+/// assert_eq!(
+///   lint_path("../con"),
+///   [PathLint::Escape { component: 0 }, PathLint::ReservedName { component: 1 }],
+/// );
+/// ```
+pub fn lint_path(path: impl AsRef<Path>) -> Vec<PathLint> {
+  let path = path.as_ref();
+  let mut lints = Vec::new();
+
+  let bytes = path.as_os_str().as_encoded_bytes();
+  if bytes.contains(&b'/') && bytes.contains(&b'\\') {
+    lints.push(PathLint::MixedSeparators);
+  }
+
+  if path.lexiclean().as_os_str() != path.as_os_str() {
+    lints.push(PathLint::NotClean);
+  }
+
+  let mut depth = 0usize;
+
+  for (i, component) in path.components().enumerate() {
+    let name = match component {
+      Component::ParentDir => {
+        match depth.checked_sub(1) {
+          Some(parent) => depth = parent,
+          None => lints.push(PathLint::Escape { component: i }),
+        }
+        continue;
+      }
+      Component::Normal(name) => {
+        depth += 1;
+        name
+      }
+      component if is_root(&component) => {
+        depth = 0;
+        continue;
+      }
+      _ => continue,
+    };
+
+    let bytes = name.as_encoded_bytes();
+
+    if has_control_character(name) {
+      lints.push(PathLint::ControlCharacter { component: i });
+    }
+
+    if bytes.len() > MAX_COMPONENT_LEN {
+      lints.push(PathLint::LongComponent {
+        component: i,
+        len: bytes.len(),
+      });
+    }
+
+    if is_reserved_name(name) {
+      lints.push(PathLint::ReservedName { component: i });
+    }
+
+    if bytes.ends_with(b".") || bytes.ends_with(b" ") {
+      lints.push(PathLint::TrailingDotOrSpace { component: i });
+    }
+  }
+
+  lints
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[track_caller]
+  fn case(path: &str, want: &[PathLint]) {
+    assert_eq!(lint_path(path), want, "{:?}", path);
+  }
+
+  #[test]
+  fn clean_paths_have_no_lints() {
+    for path in ["foo", "/foo/bar", ".", "/", "foo.txt", ".bashrc"] {
+      case(path, &[]);
+    }
+  }
+
+  #[test]
+  fn path_lints() {
+    case("foo/./bar", &[PathLint::NotClean]);
+    case(r"foo/bar\baz", &[PathLint::MixedSeparators]);
+  }
+
+  #[test]
+  fn escapes_are_linted() {
+    case("..", &[PathLint::Escape { component: 0 }]);
+    case(
+      "foo/../..",
+      &[PathLint::NotClean, PathLint::Escape { component: 2 }],
+    );
+    case("foo/../bar", &[PathLint::NotClean]);
+    case(
+      "/../foo",
+      &[PathLint::NotClean, PathLint::Escape { component: 1 }],
+    );
+  }
+
+  #[test]
+  fn component_lints() {
+    case("foo/bar\n", &[PathLint::ControlCharacter { component: 1 }]);
+    case("foo\u{9b}", &[PathLint::ControlCharacter { component: 0 }]);
+    case("NUL.txt", &[PathLint::ReservedName { component: 0 }]);
+    case(
+      "foo./bar ",
+      &[
+        PathLint::TrailingDotOrSpace { component: 0 },
+        PathLint::TrailingDotOrSpace { component: 1 },
+      ],
+    );
+    case(
+      &"a".repeat(256),
+      &[PathLint::LongComponent {
+        component: 0,
+        len: 256,
+      }],
+    );
+    case(&"a".repeat(255), &[]);
+  }
+}
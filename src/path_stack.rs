@@ -0,0 +1,181 @@
+use {
+  crate::{clean_join, is_root, Lexiclean},
+  std::path::{Component, Path, PathBuf},
+};
+
+/// An incrementally built path, which is kept clean as components are
+/// pushed and popped, for directory walkers and archive readers that build
+/// paths one level at a time.
+///
+/// Pushing and popping only examine the components being pushed and the
+/// number of components which can be popped, so maintaining the path
+/// costs nothing beyond the pushes and pops of the underlying `PathBuf`.
+///
+/// ```
+/// use lexiclean::PathStack;
+/// use std::path::Path;
+///
+/// let mut stack = PathStack::new();
+/// stack.push("src");
+/// stack.push("./bin");
+/// assert_eq!(stack.as_path(), Path::new("src/bin"));
+///
+/// assert!(stack.pop());
+/// stack.push("../tests");
+/// assert_eq!(stack.finish(), Path::new("tests"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathStack {
+  normals: usize,
+  path: PathBuf,
+}
+
+impl PathStack {
+  /// An empty stack, whose path is `.`.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// A stack starting at the cleaned form of `base`, whose components may
+  /// be popped like pushed components.
+  pub fn with_base(base: impl AsRef<Path>) -> Self {
+    let mut stack = Self::new();
+    stack.push(base.as_ref().lexiclean());
+    stack
+  }
+
+  /// The number of `Normal` components at the end of the path, which is
+  /// the number of times `pop` will succeed.
+  pub fn depth(&self) -> usize {
+    self.normals
+  }
+
+  /// Push each component of `path`. `.` components are ignored, and `..`
+  /// components pop a component, like `Lexiclean::lexiclean`. As with
+  /// `PathBuf::push`, if `path` is absolute, it replaces the path.
+  pub fn push(&mut self, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+
+    if let Some(Component::Prefix(_)) | Some(Component::RootDir) = path.components().next() {
+      let joined = clean_join(&self.path, path);
+      self.path.clear();
+      self.normals = 0;
+      for component in joined.components() {
+        self.push_component(component);
+      }
+      return;
+    }
+
+    for component in path.components() {
+      self.push_component(component);
+    }
+  }
+
+  fn push_component(&mut self, component: Component) {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        if !self.pop()
+          && !self
+            .path
+            .components()
+            .next_back()
+            .is_some_and(|c| is_root(&c))
+        {
+          self.path.push(component);
+        }
+      }
+      Component::Normal(_) => {
+        self.path.push(component);
+        self.normals += 1;
+      }
+      Component::Prefix(_) | Component::RootDir => self.path.push(component),
+    }
+  }
+
+  /// Remove the last component, returning `false` if there is no `Normal`
+  /// component to remove.
+  pub fn pop(&mut self) -> bool {
+    if self.normals == 0 {
+      return false;
+    }
+
+    self.path.pop();
+    self.normals -= 1;
+    true
+  }
+
+  /// The cleaned path, which is `.` if it is empty.
+  pub fn as_path(&self) -> &Path {
+    if self.path.as_os_str().is_empty() {
+      Path::new(Component::CurDir.as_os_str())
+    } else {
+      &self.path
+    }
+  }
+
+  /// The cleaned path, which is `.` if it is empty.
+  pub fn finish(self) -> PathBuf {
+    if self.path.as_os_str().is_empty() {
+      Component::CurDir.as_os_str().into()
+    } else {
+      self.path
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn pushed_paths_are_cleaned() {
+    for (base, pushed) in [
+      ("", &["foo", "./bar", "..", "baz/"][..]),
+      ("", &["..", "foo", "../.."]),
+      ("/", &["..", "foo"]),
+      ("/foo/bar", &["../../..", "baz"]),
+      ("foo", &["/bar", "../baz"]),
+      ("a/./b", &["", "."]),
+      ("..", &["..", "a", ".."]),
+    ] {
+      let mut stack = PathStack::with_base(base);
+      let mut path = PathBuf::from(base);
+
+      for pushed in pushed {
+        stack.push(pushed);
+        path.push(pushed);
+        assert_eq!(
+          stack.as_path(),
+          path.as_path().lexiclean(),
+          "{} {:?}",
+          base,
+          pushed
+        );
+      }
+
+      assert_eq!(
+        stack.finish().as_os_str(),
+        path.as_path().lexiclean().as_os_str()
+      );
+    }
+  }
+
+  #[test]
+  fn only_normal_components_are_popped() {
+    let mut stack = PathStack::with_base("../a/b");
+    assert_eq!(stack.depth(), 2);
+    assert!(stack.pop());
+    assert!(stack.pop());
+    assert!(!stack.pop());
+    assert_eq!(stack.as_path(), Path::new(".."));
+
+    let mut stack = PathStack::with_base("/");
+    assert!(!stack.pop());
+    assert_eq!(stack.finish(), Path::new("/"));
+
+    let mut stack = PathStack::new();
+    assert!(!stack.pop());
+    assert_eq!(stack.finish().as_os_str(), ".");
+  }
+}
@@ -0,0 +1,140 @@
+use {
+  crate::{resolve_under_root, Error},
+  std::path::{Path, PathBuf},
+};
+
+/// Percent-decode the path of an HTTP request, so that encoded dot segments,
+/// like `%2e%2e`, are seen by cleaning and containment checks, instead of
+/// being decoded later to bypass them.
+///
+/// Fails with `Error::Encoding` if a `%` is not followed by two hexadecimal
+/// digits, if a separator, `/` or `\`, is encoded, since decoding it would
+/// change how the path is split into components, or if the decoded path
+/// still contains a percent-encoded byte, since it was encoded more than
+/// once, like `%252e`. Fails with `Error::Nul` if a NUL byte is encoded, and
+/// with `Error::Unicode` if the decoded path is not valid UTF-8.
+///
+/// ```
+/// use lexiclean::{percent_decode_path, Error};
+///
+/// assert_eq!(percent_decode_path("/%2e%2e/my%20file").unwrap(), "/../my file");
+/// assert_eq!(percent_decode_path("/..%2fetc"), Err(Error::Encoding));
+/// assert_eq!(percent_decode_path("/%252e%252e"), Err(Error::Encoding));
+/// ```
+pub fn percent_decode_path(path: &str) -> Result<String, Error> {
+  let mut bytes = Vec::with_capacity(path.len());
+  let mut rest = path.as_bytes();
+
+  while let Some((&byte, tail)) = rest.split_first() {
+    if byte != b'%' {
+      bytes.push(byte);
+      rest = tail;
+      continue;
+    }
+
+    let byte = tail.get(..2).and_then(decode_hex).ok_or(Error::Encoding)?;
+
+    match byte {
+      0 => return Err(Error::Nul),
+      b'/' | b'\\' => return Err(Error::Encoding),
+      _ => bytes.push(byte),
+    }
+
+    rest = &tail[2..];
+  }
+
+  if bytes
+    .windows(3)
+    .any(|window| window[0] == b'%' && decode_hex(&window[1..]).is_some())
+  {
+    return Err(Error::Encoding);
+  }
+
+  String::from_utf8(bytes).map_err(|_| Error::Unicode)
+}
+
+/// Percent-decode `request_path` with `percent_decode_path`, and resolve it
+/// under `root` with `resolve_under_root`, failing like either.
+///
+/// ```
+/// use lexiclean::{resolve_encoded_under_root, Error};
+///
+/// assert_eq!(
+///   resolve_encoded_under_root("/srv/www", "/my%20site/index.html"),
+///   Ok("/srv/www/my site/index.html".into()),
+/// );
+/// assert_eq!(
+///   resolve_encoded_under_root("/srv/www", "/%2e%2e/etc/passwd"),
+///   Err(Error::Escape),
+/// );
+/// ```
+pub fn resolve_encoded_under_root(
+  root: impl AsRef<Path>,
+  request_path: &str,
+) -> Result<PathBuf, Error> {
+  resolve_under_root(root, &percent_decode_path(request_path)?)
+}
+
+fn decode_hex(hex: &[u8]) -> Option<u8> {
+  let digit = |byte: u8| char::from(byte).to_digit(16);
+  Some((digit(hex[0])? * 16 + digit(hex[1])?) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn paths_are_decoded() {
+    for (path, want) in [
+      ("", ""),
+      ("/foo/bar", "/foo/bar"),
+      ("/%2e%2e", "/.."),
+      ("/%2E%2E/%2e", "/../."),
+      ("/.%2e/", "/../"),
+      ("/my%20file", "/my file"),
+      ("/%C3%A9t%C3%A9", "/été"),
+      ("/100%25", "/100%"),
+      ("/%25zz", "/%zz"),
+    ] {
+      assert_eq!(percent_decode_path(path).unwrap(), want, "{}", path);
+    }
+  }
+
+  #[test]
+  fn invalid_encodings_are_rejected() {
+    for (path, want) in [
+      ("/%", Error::Encoding),
+      ("/%2", Error::Encoding),
+      ("/%zz", Error::Encoding),
+      ("/..%2f..", Error::Encoding),
+      ("/..%2F..", Error::Encoding),
+      ("/..%5c..", Error::Encoding),
+      ("/%252e%252e", Error::Encoding),
+      ("/%25%32%65", Error::Encoding),
+      ("/foo%00", Error::Nul),
+      ("/%ff", Error::Unicode),
+    ] {
+      assert_eq!(percent_decode_path(path), Err(want), "{}", path);
+    }
+  }
+
+  #[test]
+  fn encoded_paths_are_resolved_under_root() {
+    for (path, want) in [
+      ("/css/site.css", Ok("/srv/www/css/site.css")),
+      ("/css/%2e%2e/index.html", Ok("/srv/www/index.html")),
+      ("/%2e%2e", Err(Error::Escape)),
+      ("/.%2e/.%2e/etc/passwd", Err(Error::Escape)),
+      ("/%2e%2e%2fetc", Err(Error::Encoding)),
+      ("/foo%0abar", Err(Error::ControlCharacter)),
+    ] {
+      assert_eq!(
+        resolve_encoded_under_root("/srv/www", path),
+        want.map(PathBuf::from),
+        "{}",
+        path
+      );
+    }
+  }
+}
@@ -0,0 +1,604 @@
+use {
+  alloc::{format, string::String, vec::Vec},
+  core::{
+    cmp::Ordering,
+    fmt::{self, Debug, Display, Formatter},
+    hash::Hash,
+    marker::PhantomData,
+  },
+};
+
+/// The flavor of a `PurePath`, either `Posix` or `Windows`.
+pub trait Flavor: Copy + Debug + Ord + Hash + private::Sealed {
+  #[doc(hidden)]
+  const WINDOWS: bool;
+}
+
+/// POSIX paths, with `/` separators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Posix {}
+
+/// Windows paths, with `\` and `/` separators, and drive, UNC, device, and
+/// verbatim prefixes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Windows {}
+
+impl Flavor for Posix {
+  const WINDOWS: bool = false;
+}
+
+impl Flavor for Windows {
+  const WINDOWS: bool = true;
+}
+
+mod private {
+  pub trait Sealed {}
+
+  impl Sealed for super::Posix {}
+
+  impl Sealed for super::Windows {}
+}
+
+pub type PurePosixPath = PurePath<Posix>;
+
+pub type PureWindowsPath = PurePath<Windows>;
+
+/// A path of flavor `F`, which is parsed, joined, cleaned, and rendered
+/// according to the rules of that flavor, regardless of the host platform.
+///
+/// ```
+/// use lexiclean::{PurePosixPath, PureWindowsPath};
+///
+/// assert_eq!(PurePosixPath::new("/foo/../bar").lexiclean().as_str(), "/bar");
+/// assert_eq!(PureWindowsPath::new(r"C:\foo/..\bar").lexiclean().as_str(), r"C:\bar");
+/// ```
+///
+/// Cleaning follows the same rules as `Lexiclean::lexiclean` on the host
+/// platform of the flavor. Cleaned paths are rendered with the flavor's
+/// separator, `/` for `Posix` and `\` for `Windows`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PurePath<F: Flavor> {
+  flavor: PhantomData<F>,
+  path: String,
+}
+
+/// A component of a `PurePath`, the analog of `std::path::Component`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PureComponent<'a> {
+  /// A Windows prefix, like `C:`, `\\server\share`, `\\.\device`, or
+  /// `\\?\C:`, as it appears in the path.
+  Prefix(&'a str),
+  RootDir,
+  CurDir,
+  ParentDir,
+  Normal(&'a str),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PrefixKind {
+  Disk,
+  Verbatim,
+  Other,
+}
+
+impl<F: Flavor> PurePath<F> {
+  pub fn new(path: impl Into<String>) -> Self {
+    Self {
+      flavor: PhantomData,
+      path: path.into(),
+    }
+  }
+
+  pub fn as_str(&self) -> &str {
+    &self.path
+  }
+
+  pub fn into_string(self) -> String {
+    self.path
+  }
+
+  /// The components of the path, which, like `std::path::Components`, omit
+  /// repeated separators and `.` components, other than a leading `.` in a
+  /// relative path.
+  pub fn components(&self) -> impl Iterator<Item = PureComponent<'_>> {
+    parse::<F>(&self.path).1.into_iter()
+  }
+
+  /// Returns `true` if the path does not depend on a current directory.
+  pub fn is_absolute(&self) -> bool {
+    match parse::<F>(&self.path) {
+      (Some(PrefixKind::Disk), components) => components.get(1) == Some(&PureComponent::RootDir),
+      (Some(PrefixKind::Verbatim | PrefixKind::Other), _) => true,
+      (None, components) => !F::WINDOWS && components.first() == Some(&PureComponent::RootDir),
+    }
+  }
+
+  /// Join `tail` onto the path, like `Path::join`. If `tail` has a prefix, it
+  /// replaces the path, and if it has a root but no prefix, it replaces
+  /// everything but the prefix of the path.
+  pub fn join(&self, tail: impl AsRef<str>) -> Self {
+    let tail = tail.as_ref();
+
+    if parse_prefix::<F>(tail).is_some() {
+      return Self::new(tail);
+    }
+
+    if tail.chars().next().is_some_and(is_separator::<F>) {
+      let prefix = parse_prefix::<F>(&self.path).map_or("", |(prefix, _)| prefix);
+      return Self::new(format!("{}{}", prefix, tail));
+    }
+
+    let mut path = self.path.clone();
+
+    let bare_disk =
+      matches!(parse_prefix::<F>(&path), Some((prefix, PrefixKind::Disk)) if prefix == path);
+
+    if !path.is_empty() && !path.ends_with(is_separator::<F>) && !bare_disk {
+      path.push(separator::<F>());
+    }
+
+    path.push_str(tail);
+
+    Self::new(path)
+  }
+
+  /// Clean the path. Like `Lexiclean::lexiclean`, paths with a Windows
+  /// verbatim prefix are returned unchanged.
+  pub fn lexiclean(&self) -> Self {
+    Self::new(clean::<F>(&self.path))
+  }
+
+  /// Returns `true` if the cleaned form of the path is lexically inside, or
+  /// equal to, the cleaned form of `ancestor`, like `Lexiclean::is_within`.
+  pub fn is_within(&self, ancestor: &Self) -> bool {
+    use PureComponent::*;
+
+    let path = self.lexiclean();
+    let ancestor = ancestor.lexiclean();
+
+    // UNC and device prefixes are roots, with or without a root directory.
+    fn split<F: Flavor>(path: &PurePath<F>) -> (Vec<PureComponent<'_>>, usize, usize) {
+      let (kind, mut components) = parse::<F>(&path.path);
+      let rooted = matches!(kind, Some(PrefixKind::Verbatim | PrefixKind::Other));
+      components.retain(|component| *component != CurDir && !(rooted && *component == RootDir));
+      let root = components
+        .iter()
+        .take_while(|component| matches!(component, Prefix(_) | RootDir))
+        .count();
+      let parents = components[root..]
+        .iter()
+        .take_while(|component| **component == ParentDir)
+        .count();
+      (components, root, parents)
+    }
+
+    let (path, path_root, path_parents) = split(&path);
+    let (ancestor, ancestor_root, ancestor_parents) = split(&ancestor);
+
+    // Drive letters are compared case-insensitively, like `Prefix::Disk`.
+    let root_eq = |a: &PureComponent, b: &PureComponent| match (a, b) {
+      (Prefix(a), Prefix(b)) => match (disk_letter(a), disk_letter(b)) {
+        (Some(a_letter), Some(b_letter)) => {
+          a.len() == b.len() && a_letter.eq_ignore_ascii_case(&b_letter)
+        }
+        _ => a == b,
+      },
+      _ => a == b,
+    };
+
+    if path_root != ancestor_root
+      || !path[..path_root]
+        .iter()
+        .zip(&ancestor[..ancestor_root])
+        .all(|(a, b)| root_eq(a, b))
+    {
+      return false;
+    }
+
+    match path_parents.cmp(&ancestor_parents) {
+      Ordering::Greater => false,
+      Ordering::Less => ancestor_root + ancestor_parents == ancestor.len(),
+      Ordering::Equal => path[path_root..].starts_with(&ancestor[ancestor_root..]),
+    }
+  }
+}
+
+impl<F: Flavor> Display for PurePath<F> {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{}", self.path)
+  }
+}
+
+impl<F: Flavor> AsRef<str> for PurePath<F> {
+  fn as_ref(&self) -> &str {
+    &self.path
+  }
+}
+
+impl<F: Flavor> From<&str> for PurePath<F> {
+  fn from(path: &str) -> Self {
+    Self::new(path)
+  }
+}
+
+impl<F: Flavor> From<String> for PurePath<F> {
+  fn from(path: String) -> Self {
+    Self::new(path)
+  }
+}
+
+/// Clean `path` as a POSIX path, with `/` as the only separator, and `/` as
+/// the only root, regardless of the host platform.
+///
+/// ```
+/// assert_eq!(lexiclean::lexiclean_posix(r"/foo/../C:\bar"), r"/C:\bar");
+/// ```
+pub fn lexiclean_posix(path: &str) -> String {
+  clean::<Posix>(path)
+}
+
+/// Clean `path` as a Windows path, with `\` and `/` as separators, and
+/// drive, UNC, device, and verbatim prefixes, regardless of the host
+/// platform. Cleaned paths use `\` as their separator.
+///
+/// ```
+/// assert_eq!(lexiclean::lexiclean_windows(r"C:/foo\..\bar"), r"C:\bar");
+/// assert_eq!(lexiclean::lexiclean_windows(r"\\server\share\.."), r"\\server\share\");
+/// ```
+pub fn lexiclean_windows(path: &str) -> String {
+  clean::<Windows>(path)
+}
+
+pub(crate) fn separator<F: Flavor>() -> char {
+  if F::WINDOWS {
+    '\\'
+  } else {
+    '/'
+  }
+}
+
+pub(crate) fn is_separator<F: Flavor>(c: char) -> bool {
+  c == '/' || F::WINDOWS && c == '\\'
+}
+
+fn clean<F: Flavor>(path: &str) -> String {
+  use PureComponent::*;
+
+  let (kind, components) = parse::<F>(path);
+
+  if kind == Some(PrefixKind::Verbatim) {
+    return path.into();
+  }
+
+  let mut cleaned = Vec::new();
+
+  for component in components {
+    match component {
+      CurDir => {}
+      ParentDir => match cleaned.last() {
+        Some(Normal(_)) => {
+          cleaned.pop();
+        }
+        Some(RootDir) => {}
+        Some(Prefix(_)) if kind != Some(PrefixKind::Disk) => {}
+        _ => cleaned.push(component),
+      },
+      Prefix(_) | RootDir | Normal(_) => cleaned.push(component),
+    }
+  }
+
+  let mut out = String::new();
+  let mut separate = false;
+
+  for component in cleaned {
+    match component {
+      Prefix(prefix) => out.extend(prefix.chars().map(|c| {
+        if is_separator::<F>(c) {
+          separator::<F>()
+        } else {
+          c
+        }
+      })),
+      RootDir => out.push(separator::<F>()),
+      CurDir | ParentDir | Normal(_) => {
+        if separate {
+          out.push(separator::<F>());
+        }
+        out.push_str(match component {
+          Normal(name) => name,
+          _ => "..",
+        });
+        separate = true;
+      }
+    }
+  }
+
+  if out.is_empty() {
+    out.push('.');
+  }
+
+  out
+}
+
+fn parse<F: Flavor>(path: &str) -> (Option<PrefixKind>, Vec<PureComponent<'_>>) {
+  let mut components = Vec::new();
+
+  let (kind, rest) = match parse_prefix::<F>(path) {
+    Some((prefix, kind)) => {
+      components.push(PureComponent::Prefix(prefix));
+      (Some(kind), &path[prefix.len()..])
+    }
+    None => (None, path),
+  };
+
+  let separator = |c: char| {
+    if kind == Some(PrefixKind::Verbatim) {
+      c == '\\'
+    } else {
+      is_separator::<F>(c)
+    }
+  };
+
+  if rest.starts_with(separator) {
+    components.push(PureComponent::RootDir);
+  }
+
+  for (i, name) in rest
+    .split(separator)
+    .filter(|name| !name.is_empty())
+    .enumerate()
+  {
+    components.push(match name {
+      "." if i == 0 && components.is_empty() => PureComponent::CurDir,
+      "." => continue,
+      ".." => PureComponent::ParentDir,
+      _ => PureComponent::Normal(name),
+    });
+  }
+
+  (kind, components)
+}
+
+/// The drive letter of a disk prefix, like `C:`, or a verbatim disk prefix,
+/// like `\\?\C:`.
+fn disk_letter(prefix: &str) -> Option<u8> {
+  let disk = prefix.strip_prefix(r"\\?\").unwrap_or(prefix).as_bytes();
+
+  if disk.len() == 2 && disk[0].is_ascii_alphabetic() && disk[1] == b':' {
+    Some(disk[0])
+  } else {
+    None
+  }
+}
+
+/// Split the Windows prefix off of `path`, returning the prefix and its kind.
+pub(crate) fn parse_prefix<F: Flavor>(path: &str) -> Option<(&str, PrefixKind)> {
+  if !F::WINDOWS {
+    return None;
+  }
+
+  // The length of the leading component of `s`, ending at a separator.
+  fn component(s: &str, separator: fn(char) -> bool) -> usize {
+    s.find(separator).unwrap_or(s.len())
+  }
+
+  // The length of two leading components of `s`, like `server\share`.
+  fn components(s: &str, separator: fn(char) -> bool) -> usize {
+    let first = component(s, separator);
+    if first == s.len() {
+      first
+    } else {
+      first + 1 + component(&s[first + 1..], separator)
+    }
+  }
+
+  let backslash = |c| c == '\\';
+  let bytes = path.as_bytes();
+
+  if let Some(rest) = path.strip_prefix(r"\\?\") {
+    let len = if let Some(unc) = rest.strip_prefix(r"UNC\") {
+      4 + components(unc, backslash)
+    } else if rest.as_bytes().get(1) == Some(&b':') && rest.as_bytes()[0].is_ascii_alphabetic() {
+      2
+    } else {
+      component(rest, backslash)
+    };
+
+    return Some((&path[..4 + len], PrefixKind::Verbatim));
+  }
+
+  let separator = |byte: Option<&u8>| byte.is_some_and(|&byte| is_separator::<F>(byte.into()));
+
+  if separator(bytes.first()) && separator(bytes.get(1)) {
+    if bytes.get(2) == Some(&b'.') && separator(bytes.get(3)) {
+      let len = 4 + component(&path[4..], is_separator::<F>);
+      return Some((&path[..len], PrefixKind::Other));
+    }
+
+    let len = 2 + components(&path[2..], is_separator::<F>);
+    return Some((&path[..len], PrefixKind::Other));
+  }
+
+  if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+    return Some((&path[..2], PrefixKind::Disk));
+  }
+
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[track_caller]
+  fn posix(path: &str, want: &str) {
+    assert_eq!(PurePosixPath::new(path).lexiclean().as_str(), want);
+    assert_eq!(lexiclean_posix(path), want);
+  }
+
+  #[track_caller]
+  fn windows(path: &str, want: &str) {
+    assert_eq!(PureWindowsPath::new(path).lexiclean().as_str(), want);
+    assert_eq!(lexiclean_windows(path), want);
+  }
+
+  #[test]
+  fn posix_paths_are_cleaned() {
+    posix("", ".");
+    posix(".", ".");
+    posix("foo/./bar", "foo/bar");
+    posix("foo//bar/", "foo/bar");
+    posix("foo/..", ".");
+    posix("../foo/../..", "../..");
+    posix("/..", "/");
+    posix("//foo/../bar", "/bar");
+    posix(r"foo\..\bar", r"foo\..\bar");
+    posix("C:/..", ".");
+  }
+
+  #[test]
+  fn windows_paths_are_cleaned() {
+    windows("", ".");
+    windows(r"foo\.\bar", r"foo\bar");
+    windows("foo/bar/../baz", r"foo\baz");
+    windows(r"\foo\..\..", r"\");
+    windows(r"C:\..", r"C:\");
+    windows(r"C:/foo/../bar", r"C:\bar");
+    windows(r"C:..", r"C:..");
+    windows(r"C:foo\..\..", r"C:..");
+    windows(r"C:foo\..", r"C:");
+    windows(r"c:\foo", r"c:\foo");
+  }
+
+  #[test]
+  fn windows_unc_and_device_roots_are_not_popped() {
+    windows(r"\\server\share\..\..", r"\\server\share\");
+    windows(r"//server/share/foo/../bar", r"\\server\share\bar");
+    windows(r"\\server\share", r"\\server\share");
+    windows(r"\\.\pipe\foo\..\..", r"\\.\pipe\");
+    windows(r"//./COM1", r"\\.\COM1");
+  }
+
+  #[test]
+  fn windows_verbatim_paths_are_not_cleaned() {
+    windows(r"\\?\C:\foo\..\.", r"\\?\C:\foo\..\.");
+    windows(r"\\?\UNC\server\share\..", r"\\?\UNC\server\share\..");
+    windows(r"\\?\foo/../bar", r"\\?\foo/../bar");
+  }
+
+  #[test]
+  fn components_are_parsed() {
+    use PureComponent::*;
+
+    assert_eq!(
+      PurePosixPath::new("./foo//./../bar/")
+        .components()
+        .collect::<Vec<_>>(),
+      [CurDir, Normal("foo"), ParentDir, Normal("bar")],
+    );
+
+    assert_eq!(
+      PureWindowsPath::new(r"C:\foo/bar")
+        .components()
+        .collect::<Vec<_>>(),
+      [Prefix("C:"), RootDir, Normal("foo"), Normal("bar")],
+    );
+
+    assert_eq!(
+      PureWindowsPath::new(r"\\server\share\foo")
+        .components()
+        .collect::<Vec<_>>(),
+      [Prefix(r"\\server\share"), RootDir, Normal("foo")],
+    );
+
+    assert_eq!(
+      PureWindowsPath::new(r"\\?\UNC\server\share\foo/bar")
+        .components()
+        .collect::<Vec<_>>(),
+      [Prefix(r"\\?\UNC\server\share"), RootDir, Normal("foo/bar")],
+    );
+
+    assert_eq!(
+      PureWindowsPath::new(r"C:foo")
+        .components()
+        .collect::<Vec<_>>(),
+      [Prefix("C:"), Normal("foo")],
+    );
+  }
+
+  #[test]
+  fn absolute_paths() {
+    assert!(PurePosixPath::new("/foo").is_absolute());
+    assert!(!PurePosixPath::new("foo").is_absolute());
+    assert!(!PurePosixPath::new("C:/foo").is_absolute());
+    assert!(PureWindowsPath::new(r"C:\foo").is_absolute());
+    assert!(PureWindowsPath::new(r"\\server\share").is_absolute());
+    assert!(PureWindowsPath::new(r"\\?\foo").is_absolute());
+    assert!(!PureWindowsPath::new(r"C:foo").is_absolute());
+    assert!(!PureWindowsPath::new(r"\foo").is_absolute());
+    assert!(!PureWindowsPath::new(r"foo").is_absolute());
+  }
+
+  #[test]
+  fn paths_are_joined() {
+    let join = |base: &str, tail: &str| PurePosixPath::new(base).join(tail).into_string();
+    assert_eq!(join("foo", "bar"), "foo/bar");
+    assert_eq!(join("foo/", "bar"), "foo/bar");
+    assert_eq!(join("", "bar"), "bar");
+    assert_eq!(join("foo", "/bar"), "/bar");
+
+    let join = |base: &str, tail: &str| PureWindowsPath::new(base).join(tail).into_string();
+    assert_eq!(join(r"C:\foo", "bar"), r"C:\foo\bar");
+    assert_eq!(join(r"C:\foo/", "bar"), r"C:\foo/bar");
+    assert_eq!(join(r"C:", "bar"), r"C:bar");
+    assert_eq!(join(r"C:\foo", r"\bar"), r"C:\bar");
+    assert_eq!(join(r"\\server\share\foo", "/bar"), r"\\server\share/bar");
+    assert_eq!(join(r"C:\foo", r"D:bar"), r"D:bar");
+  }
+
+  #[test]
+  fn containment_is_checked() {
+    for (path, ancestor, within) in [
+      ("/a/b/c", "/a", true),
+      ("/a/./b", "/a/c/..", true),
+      ("foo/../bar", "foo", false),
+      ("/foo/barbaz", "/foo/bar", false),
+      ("..", ".", false),
+      ("a", "..", true),
+      ("a", "../b", false),
+      ("/a", "a", false),
+      ("/", "/", true),
+    ] {
+      assert_eq!(
+        PurePosixPath::new(path).is_within(&PurePosixPath::new(ancestor)),
+        within,
+        "{} {}",
+        path,
+        ancestor
+      );
+    }
+
+    for (path, ancestor, within) in [
+      (r"C:\a\b", r"C:/a", true),
+      (r"C:\a", r"D:\a", false),
+      (r"C:\a\b", r"c:\a", true),
+      (r"c:a", r"C:", true),
+      (r"\\?\C:\a", r"\\?\c:\", true),
+      (r"\\?\C:\a", r"C:\", false),
+      (r"\\server\share\a", r"\\SERVER\share", false),
+      (r"C:a", r"C:", true),
+      (r"C:a", r"C:\", false),
+      (r"\\server\share\..\x", r"\\server\share", true),
+      (r"\\server\other", r"\\server\share", false),
+      (r"\a", r"C:\", false),
+    ] {
+      assert_eq!(
+        PureWindowsPath::new(path).is_within(&PureWindowsPath::new(ancestor)),
+        within,
+        "{} {}",
+        path,
+        ancestor
+      );
+    }
+  }
+}
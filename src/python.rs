@@ -0,0 +1,133 @@
+//! Python bindings, available with the `python` feature.
+//!
+//! The `lexiclean` Python module has `lexiclean`, which cleans native paths
+//! like `Lexiclean::lexiclean` and returns a `pathlib.Path`, and
+//! `lexiclean_posix` and `lexiclean_windows`, which clean strings as POSIX and
+//! Windows paths. `normpath_posix` and `normpath_windows` clean strings
+//! exactly like `posixpath.normpath` and `ntpath.normpath`, using
+//! `Compat::Python`.
+
+use {
+  crate::{Compat, Lexiclean, PurePosixPath, PureWindowsPath},
+  pyo3::prelude::*,
+  std::path::PathBuf,
+};
+
+/// Clean the native path `path`, which may be a `str` or `os.PathLike`.
+#[pyfunction(name = "lexiclean")]
+fn clean(path: PathBuf) -> PathBuf {
+  path.lexiclean()
+}
+
+/// Clean `path` as a POSIX path, regardless of the host platform.
+#[pyfunction]
+fn lexiclean_posix(path: &str) -> String {
+  crate::lexiclean_posix(path)
+}
+
+/// Clean `path` as a Windows path, regardless of the host platform.
+#[pyfunction]
+fn lexiclean_windows(path: &str) -> String {
+  crate::lexiclean_windows(path)
+}
+
+/// Clean `path` like `posixpath.normpath`.
+#[pyfunction]
+fn normpath_posix(path: &str) -> String {
+  PurePosixPath::new(path)
+    .lexiclean_compat(Compat::Python)
+    .into_string()
+}
+
+/// Clean `path` like `ntpath.normpath`.
+#[pyfunction]
+fn normpath_windows(path: &str) -> String {
+  PureWindowsPath::new(path)
+    .lexiclean_compat(Compat::Python)
+    .into_string()
+}
+
+/// Lexically clean paths.
+#[pymodule]
+pub fn lexiclean(module: &Bound<PyModule>) -> PyResult<()> {
+  module.add_function(wrap_pyfunction!(clean, module)?)?;
+  module.add_function(wrap_pyfunction!(lexiclean_posix, module)?)?;
+  module.add_function(wrap_pyfunction!(lexiclean_windows, module)?)?;
+  module.add_function(wrap_pyfunction!(normpath_posix, module)?)?;
+  module.add_function(wrap_pyfunction!(normpath_windows, module)?)?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    pyo3::{types::PyDict, wrap_pymodule},
+    std::ffi::CString,
+  };
+
+  #[track_caller]
+  fn run(code: &str) {
+    Python::initialize();
+    Python::attach(|py| {
+      let globals = PyDict::new(py);
+      globals
+        .set_item("lexiclean", wrap_pymodule!(lexiclean)(py))
+        .unwrap();
+      py.run(&CString::new(code).unwrap(), Some(&globals), None)
+        .unwrap();
+    });
+  }
+
+  #[test]
+  fn native_paths_are_cleaned() {
+    run(
+      r#"
+import os, pathlib
+assert lexiclean.lexiclean("foo/./bar/..") == pathlib.Path("foo")
+assert lexiclean.lexiclean(pathlib.Path("a/b/../c")) == pathlib.Path("a/c")
+assert lexiclean.lexiclean("") == pathlib.Path(".")
+assert isinstance(lexiclean.lexiclean(os.curdir), pathlib.Path)
+"#,
+    );
+  }
+
+  #[test]
+  fn flavored_paths_are_cleaned() {
+    run(
+      r#"
+assert lexiclean.lexiclean_posix("/foo/../C:\\bar/.") == "/C:\\bar"
+assert lexiclean.lexiclean_posix("") == "."
+assert lexiclean.lexiclean_windows("C:/foo\\..\\bar") == "C:\\bar"
+assert lexiclean.lexiclean_windows("\\\\server\\share\\..") == "\\\\server\\share\\"
+"#,
+    );
+  }
+
+  #[test]
+  fn paths_are_cleaned_like_normpath() {
+    run(
+      r#"
+import ntpath, posixpath
+for path in ["", ".", "//foo/.//bar/", "///foo", "../a/../..", "/../a", "a/b/"]:
+    assert lexiclean.normpath_posix(path) == posixpath.normpath(path), path
+for path in ["C:/a/../b", "\\\\server\\share\\..", "C:", "a\\..\\.."]:
+    assert lexiclean.normpath_windows(path) == ntpath.normpath(path), path
+"#,
+    );
+  }
+
+  #[test]
+  fn non_strings_are_rejected() {
+    run(
+      r#"
+try:
+    lexiclean.lexiclean_posix(1)
+except TypeError:
+    pass
+else:
+    raise AssertionError("expected TypeError")
+"#,
+    );
+  }
+}
@@ -0,0 +1,138 @@
+use {
+  crate::{Lexiclean, Lexicleaner},
+  ::rayon::{
+    iter::Map,
+    prelude::{IntoParallelRefIterator, ParallelIterator},
+  },
+  std::path::{Path, PathBuf},
+};
+
+/// Clean every path in `paths` in parallel, like `lexiclean_all`, using
+/// rayon's global thread pool. Cleaned paths are returned in the same order
+/// as `paths`.
+///
+/// ```
+/// use lexiclean::par_lexiclean_all;
+/// use std::path::Path;
+///
+/// assert_eq!(
+///   par_lexiclean_all(&["foo/./bar", "foo/../baz", ""]),
+///   [Path::new("foo/bar"), Path::new("baz"), Path::new(".")],
+/// );
+/// ```
+pub fn par_lexiclean_all<P: AsRef<Path> + Sync>(paths: &[P]) -> Vec<PathBuf> {
+  let cleaner = Lexicleaner::new();
+  paths.par_iter().map(|path| cleaner.clean(path)).collect()
+}
+
+/// Extension trait for parallel iterators over paths, available with the
+/// `rayon` feature.
+///
+/// `par_lexicleaned` cleans every path like `Lexiclean::lexiclean`, so owned
+/// `PathBuf`s, `String`s, and `OsString`s reuse their buffers. The result is
+/// an indexed parallel iterator if `self` is, so collecting it preserves the
+/// order of the input.
+///
+/// ```
+/// use {lexiclean::ParLexicleaned, rayon::prelude::*, std::path::PathBuf};
+///
+/// let paths = vec![PathBuf::from("a/./b"), PathBuf::from("a/../c")];
+///
+/// assert_eq!(
+///   paths.into_par_iter().par_lexicleaned().collect::<Vec<PathBuf>>(),
+///   [PathBuf::from("a/b"), PathBuf::from("c")],
+/// );
+/// ```
+pub trait ParLexicleaned: ParallelIterator + Sized
+where
+  Self::Item: Lexiclean,
+  <Self::Item as Lexiclean>::Output: Send,
+{
+  fn par_lexicleaned(self) -> Map<Self, Clean<Self>> {
+    self.map(Lexiclean::lexiclean)
+  }
+}
+
+type Clean<I> = fn(<I as ParallelIterator>::Item) -> Cleaned<I>;
+
+type Cleaned<I> = <<I as ParallelIterator>::Item as Lexiclean>::Output;
+
+impl<I> ParLexicleaned for I
+where
+  I: ParallelIterator,
+  I::Item: Lexiclean,
+  <I::Item as Lexiclean>::Output: Send,
+{
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::lexiclean_all,
+    ::rayon::prelude::{IndexedParallelIterator, IntoParallelIterator},
+    std::{ffi::OsString, sync::Arc},
+  };
+
+  fn paths() -> Vec<String> {
+    (0..10_000)
+      .map(|i| match i % 5 {
+        0 => format!("{}/./a", i),
+        1 => format!("a/{}/../../b/{}", i, i),
+        2 => format!("/../{}//", i),
+        3 => String::new(),
+        _ => format!("../{}/.", i),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn parallel_cleaning_matches_sequential_cleaning() {
+    let paths = paths();
+    assert_eq!(par_lexiclean_all(&paths), lexiclean_all(&paths));
+    assert!(par_lexiclean_all::<&str>(&[]).is_empty());
+  }
+
+  #[test]
+  fn parallel_iterators_are_cleaned_in_order() {
+    let paths = paths();
+    let sequential = lexiclean_all(&paths);
+
+    assert_eq!(
+      paths.par_iter().par_lexicleaned().collect::<Vec<PathBuf>>(),
+      sequential,
+    );
+
+    assert_eq!(
+      paths
+        .clone()
+        .into_par_iter()
+        .map(PathBuf::from)
+        .par_lexicleaned()
+        .collect::<Vec<PathBuf>>(),
+      sequential,
+    );
+
+    assert_eq!(
+      paths
+        .into_par_iter()
+        .map(OsString::from)
+        .par_lexicleaned()
+        .with_min_len(1)
+        .collect::<Vec<PathBuf>>(),
+      sequential,
+    );
+  }
+
+  #[test]
+  fn smart_pointers_clean_to_smart_pointers() {
+    let paths = vec![Arc::<Path>::from(Path::new("a/./b")), Path::new("c").into()];
+    let cleaned = paths
+      .clone()
+      .into_par_iter()
+      .par_lexicleaned()
+      .collect::<Vec<Arc<Path>>>();
+    assert_eq!(cleaned[0], Arc::from(Path::new("a/b")));
+    assert!(Arc::ptr_eq(&cleaned[1], &paths[1]));
+  }
+}
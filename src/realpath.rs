@@ -0,0 +1,95 @@
+use {
+  crate::{clean_join, Error, Lexicleaner},
+  std::path::{Component, Path, PathBuf},
+};
+
+/// Resolve `path` against `current_dir` like GNU `realpath --logical
+/// --canonicalize-missing`, without touching the filesystem: `path` is
+/// joined onto `current_dir`, and the result is cleaned, so `..` components
+/// remove the preceding component, even if it is a symlink, and `..`
+/// components that would ascend above the root are dropped. Unlike
+/// `realpath`, an empty `path` resolves to `current_dir`.
+///
+/// ```
+/// use lexiclean::realpath_logical;
+/// use std::path::Path;
+///
+/// assert_eq!(realpath_logical("/home/user", "../bin/"), Path::new("/home/bin"));
+/// assert_eq!(realpath_logical("/home/user", "/../etc"), Path::new("/etc"));
+/// ```
+pub fn realpath_logical(current_dir: impl AsRef<Path>, path: impl AsRef<Path>) -> PathBuf {
+  clean_join(current_dir, path)
+}
+
+/// Resolve `path` against `current_dir` like `realpath_logical`, but fail
+/// with `Error::Escape` instead of dropping `..` components that would
+/// ascend above the root, or keeping `..` components that remain at the
+/// start of the result, which is only possible if `current_dir` is
+/// relative.
+pub fn try_realpath_logical(
+  current_dir: impl AsRef<Path>,
+  path: impl AsRef<Path>,
+) -> Result<PathBuf, Error> {
+  let resolved = Lexicleaner::new()
+    .deny_escape(true)
+    .try_clean(current_dir.as_ref().join(path))?;
+
+  if resolved.components().next() == Some(Component::ParentDir) {
+    return Err(Error::Escape);
+  }
+
+  Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn paths_are_resolved() {
+    for (current_dir, path, want) in [
+      ("/home/user", "", "/home/user"),
+      ("/home/user", ".", "/home/user"),
+      ("/home/user", "foo/./bar/", "/home/user/foo/bar"),
+      ("/home/user", "..", "/home"),
+      ("/home/user", "../../..", "/"),
+      ("/home/user", "/etc//passwd", "/etc/passwd"),
+      ("/home/user", "//etc", "/etc"),
+      ("/", "..", "/"),
+      ("foo", "../../bar", "../bar"),
+    ] {
+      assert_eq!(
+        realpath_logical(current_dir, path),
+        Path::new(want),
+        "{} {}",
+        current_dir,
+        path
+      );
+    }
+  }
+
+  #[test]
+  fn escaping_paths_are_rejected() {
+    assert_eq!(
+      try_realpath_logical("/home/user", "../bin"),
+      Ok("/home/bin".into())
+    );
+    assert_eq!(try_realpath_logical("foo", "bar/.."), Ok("foo".into()));
+
+    for (current_dir, path) in [
+      ("/home/user", "../../.."),
+      ("/home/user", "/.."),
+      ("/", ".."),
+      ("foo", "../.."),
+      ("", ".."),
+    ] {
+      assert_eq!(
+        try_realpath_logical(current_dir, path),
+        Err(Error::Escape),
+        "{} {}",
+        current_dir,
+        path
+      );
+    }
+  }
+}
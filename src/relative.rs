@@ -0,0 +1,291 @@
+use {
+  crate::clean_components,
+  std::{
+    cmp::Ordering,
+    iter,
+    path::{Component, Path, PathBuf},
+  },
+};
+
+pub(crate) fn lexically_relative(path: &Path, base: &Path) -> Option<PathBuf> {
+  let path = components(path);
+  let base = components(base);
+
+  let (path_root, path) = split_root(&path);
+  let (base_root, base) = split_root(&base);
+
+  if path_root != base_root {
+    return None;
+  }
+
+  let common = path.iter().zip(base).take_while(|(a, b)| a == b).count();
+
+  let mut ascend = 0;
+
+  for component in &base[common..] {
+    match component {
+      Component::Normal(_) => ascend += 1,
+      Component::ParentDir => return None,
+      _ => unreachable!(),
+    }
+  }
+
+  let relative = iter::repeat(Component::ParentDir)
+    .take(ascend)
+    .chain(path[common..].iter().copied())
+    .collect::<PathBuf>();
+
+  if relative.as_os_str().is_empty() {
+    Some(Component::CurDir.as_os_str().into())
+  } else {
+    Some(relative)
+  }
+}
+
+/// The longest path that is an ancestor of, or equal to, the cleaned forms of
+/// both `a` and `b`, compared component by component.
+///
+/// Returns `None` if `a` and `b` have different roots or prefixes. If one path
+/// has more leading `..` components than the other, the common ancestor is
+/// those `..` components.
+///
+/// ```
+/// use lexiclean::common_ancestor;
+///
+/// assert_eq!(common_ancestor("/foo/bar", "/foo/baz"), Some("/foo".into()));
+/// assert_eq!(common_ancestor("foo", "bar"), Some(".".into()));
+/// assert_eq!(common_ancestor("../foo", "bar"), Some("..".into()));
+/// assert_eq!(common_ancestor("/foo", "foo"), None);
+/// ```
+pub fn common_ancestor(a: impl AsRef<Path>, b: impl AsRef<Path>) -> Option<PathBuf> {
+  let a = components(a.as_ref());
+  let b = components(b.as_ref());
+
+  let (a_root, a) = split_root(&a);
+  let (b_root, b) = split_root(&b);
+
+  if a_root != b_root {
+    return None;
+  }
+
+  let common = if parents(a) == parents(b) {
+    &a[..a.iter().zip(b).take_while(|(a, b)| a == b).count()]
+  } else if parents(a) > parents(b) {
+    &a[..parents(a)]
+  } else {
+    &b[..parents(b)]
+  };
+
+  let ancestor = a_root.iter().chain(common).collect::<PathBuf>();
+
+  if ancestor.as_os_str().is_empty() {
+    Some(Component::CurDir.as_os_str().into())
+  } else {
+    Some(ancestor)
+  }
+}
+
+pub(crate) fn is_within(path: &Path, ancestor: &Path) -> bool {
+  let path = components(path);
+  let ancestor = components(ancestor);
+
+  let (path_root, path) = split_root(&path);
+  let (ancestor_root, ancestor) = split_root(&ancestor);
+
+  if path_root != ancestor_root {
+    return false;
+  }
+
+  match parents(path).cmp(&parents(ancestor)) {
+    Ordering::Greater => false,
+    Ordering::Less => parents(ancestor) == ancestor.len(),
+    Ordering::Equal => path.starts_with(ancestor),
+  }
+}
+
+fn parents(components: &[Component]) -> usize {
+  components
+    .iter()
+    .take_while(|component| **component == Component::ParentDir)
+    .count()
+}
+
+fn components(path: &Path) -> Vec<Component<'_>> {
+  let mut components = clean_components(path.components());
+  components.retain(|component| *component != Component::CurDir);
+  components
+}
+
+fn split_root<'a, 'b>(
+  components: &'b [Component<'a>],
+) -> (&'b [Component<'a>], &'b [Component<'a>]) {
+  components.split_at(
+    components
+      .iter()
+      .take_while(|component| matches!(component, Component::Prefix(_) | Component::RootDir))
+      .count(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{common_ancestor, Lexiclean};
+  use std::path::Path;
+
+  #[track_caller]
+  fn case(path: &str, base: &str, want: Option<&str>) {
+    assert_eq!(
+      Path::new(path).lexically_relative(base),
+      want.map(Into::into),
+    );
+  }
+
+  #[test]
+  fn sibling() {
+    case("a/b/c", "a/d", Some("../b/c"));
+    case("/a/b/c", "/a/d", Some("../b/c"));
+  }
+
+  #[test]
+  fn same_path_is_current_dir() {
+    case("a", "a/.", Some("."));
+    case("/", "/", Some("."));
+    case("", ".", Some("."));
+  }
+
+  #[test]
+  fn descendant() {
+    case("a/b/c", "a", Some("b/c"));
+  }
+
+  #[test]
+  fn ancestor() {
+    case("a/b", "a/b/c/d", Some("../.."));
+  }
+
+  #[test]
+  fn paths_are_cleaned_first() {
+    case("a/x/../b", "a/./c/", Some("../b"));
+  }
+
+  #[test]
+  fn leading_parent_dirs_in_path() {
+    case("../a", "b", Some("../../a"));
+  }
+
+  #[test]
+  fn leading_parent_dirs_in_base_are_unknown() {
+    case("a", "../b", None);
+    case("a", "..", None);
+  }
+
+  #[test]
+  fn different_roots_have_no_relative_path() {
+    case("/a", "b", None);
+    case("a", "/b", None);
+  }
+
+  #[test]
+  fn common_ancestor_is_on_component_boundaries() {
+    assert_eq!(common_ancestor("/foo/bar", "/foo/ba"), Some("/foo".into()));
+  }
+
+  #[test]
+  fn common_ancestor_of_nested_paths_is_outer_path() {
+    assert_eq!(common_ancestor("a/b/c", "a/./b/"), Some("a/b".into()));
+    assert_eq!(common_ancestor("/", "/a"), Some("/".into()));
+  }
+
+  #[test]
+  fn common_ancestor_is_found_after_cleaning() {
+    assert_eq!(common_ancestor("a/x/../b", "a/b/c"), Some("a/b".into()));
+  }
+
+  #[test]
+  fn common_ancestor_with_leading_parent_dirs() {
+    assert_eq!(common_ancestor("../a", "../b"), Some("..".into()));
+    assert_eq!(common_ancestor("../../a", "../b"), Some("../..".into()));
+    assert_eq!(common_ancestor("../a/b", "../a/c"), Some("../a".into()));
+    assert_eq!(common_ancestor("../../a", "../b/c/d"), Some("../..".into()));
+  }
+
+  #[test]
+  fn paths_with_different_roots_have_no_common_ancestor() {
+    assert_eq!(common_ancestor("/a", "a"), None);
+  }
+
+  #[test]
+  fn path_is_within_itself() {
+    assert!(Path::new("a/b").is_within("a/b"));
+    assert!(Path::new("/").is_within("/"));
+  }
+
+  #[test]
+  fn descendant_is_within_ancestor() {
+    assert!(Path::new("/a/b/c").is_within("/a"));
+    assert!(Path::new("a/b").is_within("."));
+  }
+
+  #[test]
+  fn paths_are_cleaned_before_checking_containment() {
+    assert!(!Path::new("foo/../bar").is_within("foo"));
+    assert!(Path::new("bar/../foo/baz").is_within("foo/."));
+    assert!(Path::new("/foo/bar").is_within("/foo/baz/../"));
+  }
+
+  #[test]
+  fn containment_is_on_component_boundaries() {
+    assert!(!Path::new("/foo/barbaz").is_within("/foo/bar"));
+  }
+
+  #[test]
+  fn escaping_path_is_not_within() {
+    assert!(!Path::new("..").is_within("."));
+    assert!(!Path::new("../a").is_within("a"));
+    assert!(!Path::new("../../a").is_within(".."));
+  }
+
+  #[test]
+  fn path_is_within_leading_parent_dirs() {
+    assert!(Path::new("a").is_within(".."));
+    assert!(Path::new("../a").is_within("../.."));
+  }
+
+  #[test]
+  fn containment_below_unknown_directory_is_false() {
+    assert!(!Path::new("a").is_within("../b"));
+  }
+
+  #[test]
+  fn paths_with_different_roots_are_not_within() {
+    assert!(!Path::new("/a").is_within("a"));
+    assert!(!Path::new("a").is_within("/"));
+  }
+
+  #[test]
+  fn proximate_path_is_relative_path_if_it_exists() {
+    assert_eq!(
+      Path::new("a/./b").lexically_proximate("a/c"),
+      Path::new("../b")
+    );
+  }
+
+  #[test]
+  fn proximate_path_falls_back_to_original_path() {
+    assert_eq!(
+      Path::new("/a/./b").lexically_proximate("c").as_os_str(),
+      "/a/./b",
+    );
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn proximate_path_across_drives_is_original_path() {
+    assert_eq!(
+      Path::new(r"D:\foo")
+        .lexically_proximate(r"C:\foo")
+        .as_os_str(),
+      r"D:\foo",
+    );
+  }
+}
@@ -0,0 +1,211 @@
+use {
+  crate::{check_nul, clean_join, CleanPathBuf, Error},
+  std::{
+    convert::TryFrom,
+    ffi::OsStr,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+  },
+};
+
+/// An owned path which is always relative and clean.
+///
+/// Construction fails if the path is absolute or has a Windows prefix, like
+/// `C:`, and otherwise cleans it. Leading `..` components are kept. Since a
+/// relative path is only meaningful relative to something, `RelativePathBuf`
+/// does not dereference to `Path`, and is converted to one with `to_path`,
+/// which requires a base.
+///
+/// ```
+/// use lexiclean::{Error, RelativePathBuf};
+/// use std::path::Path;
+///
+/// let path = RelativePathBuf::new("assets/./logo.png").unwrap();
+/// assert_eq!(path.to_path("/srv/www"), Path::new("/srv/www/assets/logo.png"));
+///
+/// assert_eq!(RelativePathBuf::new("/etc/passwd"), Err(Error::Absolute));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RelativePathBuf {
+  path: CleanPathBuf,
+}
+
+impl RelativePathBuf {
+  /// Clean `path`, failing with `Error::Absolute` if it is absolute or has a
+  /// prefix, and with `Error::Nul` if it contains a NUL byte.
+  pub fn new(path: impl AsRef<Path>) -> Result<Self, Error> {
+    let path = path.as_ref();
+    check(path)?;
+    Ok(Self {
+      path: CleanPathBuf::new(path),
+    })
+  }
+
+  /// The cleaned path, which is `.` if it is empty.
+  pub fn as_os_str(&self) -> &OsStr {
+    self.path.as_os_str()
+  }
+
+  pub fn join(&self, path: impl AsRef<Path>) -> Result<Self, Error> {
+    let mut joined = self.clone();
+    joined.push(path)?;
+    Ok(joined)
+  }
+
+  /// Push `path` onto `self`, keeping it clean, and failing like `new`, and
+  /// leaving `self` unchanged, if `path` is absolute, has a prefix, or
+  /// contains a NUL byte.
+  pub fn push(&mut self, path: impl AsRef<Path>) -> Result<(), Error> {
+    let path = path.as_ref();
+    check(path)?;
+    self.path.push(path);
+    Ok(())
+  }
+
+  /// Remove the first `n` components, like `tar --strip-components`.
+  /// Returns `None` if there are fewer than `n` components, or if a `..`
+  /// would be removed, since the remaining path would no longer be
+  /// relative to the same directory. Removing every component leaves `.`.
+  pub fn strip_components(&self, n: usize) -> Option<Self> {
+    let mut components = self.path.components();
+
+    for _ in 0..n {
+      match components.next()? {
+        Component::Normal(_) => {}
+        _ => return None,
+      }
+    }
+
+    Some(Self {
+      path: CleanPathBuf::new(components.as_path()),
+    })
+  }
+
+  /// Join `self` onto `base` and clean the result.
+  pub fn to_path(&self, base: impl AsRef<Path>) -> PathBuf {
+    clean_join(base, &*self.path)
+  }
+}
+
+/// Parsing fails like `RelativePathBuf::new`.
+impl FromStr for RelativePathBuf {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Error> {
+    Self::new(s)
+  }
+}
+
+/// Conversion fails like `RelativePathBuf::new`.
+impl TryFrom<&Path> for RelativePathBuf {
+  type Error = Error;
+
+  fn try_from(path: &Path) -> Result<Self, Error> {
+    Self::new(path)
+  }
+}
+
+fn check(path: &Path) -> Result<(), Error> {
+  match path.components().next() {
+    Some(Component::Prefix(_)) | Some(Component::RootDir) => Err(Error::Absolute),
+    _ => check_nul(path),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn relative_paths_are_cleaned() {
+    for (path, want) in [
+      ("foo", "foo"),
+      ("foo/./bar/", "foo/bar"),
+      ("foo/..", "."),
+      ("", "."),
+      ("../foo", "../foo"),
+    ] {
+      assert_eq!(
+        RelativePathBuf::new(path).unwrap().as_os_str(),
+        want,
+        "{}",
+        path
+      );
+    }
+  }
+
+  #[test]
+  fn absolute_paths_are_rejected() {
+    assert_eq!(RelativePathBuf::new("/foo"), Err(Error::Absolute));
+    assert_eq!(RelativePathBuf::new("/"), Err(Error::Absolute));
+
+    let mut path = RelativePathBuf::new("foo").unwrap();
+    assert_eq!(path.push("/bar"), Err(Error::Absolute));
+    assert_eq!(path.as_os_str(), "foo");
+  }
+
+  #[test]
+  fn paths_are_parsed() {
+    assert_eq!(
+      "foo/./bar".parse::<RelativePathBuf>().unwrap().as_os_str(),
+      "foo/bar"
+    );
+    assert_eq!("/foo".parse::<RelativePathBuf>(), Err(Error::Absolute));
+    assert_eq!("foo\0".parse::<RelativePathBuf>(), Err(Error::Nul));
+    assert_eq!(
+      RelativePathBuf::try_from(Path::new("/foo")),
+      Err(Error::Absolute)
+    );
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn prefixed_paths_are_rejected() {
+    for path in [r"C:foo", r"C:\foo", r"\foo", r"\\server\share\foo"] {
+      assert_eq!(RelativePathBuf::new(path), Err(Error::Absolute), "{}", path);
+    }
+  }
+
+  #[test]
+  fn paths_are_pushed() {
+    let mut path = RelativePathBuf::new("foo/bar").unwrap();
+    path.push("../baz").unwrap();
+    assert_eq!(path.as_os_str(), "foo/baz");
+
+    assert_eq!(path.join("../../..").unwrap().as_os_str(), "..");
+  }
+
+  #[test]
+  fn components_are_stripped() {
+    for (path, n, want) in [
+      ("foo/bar/baz", 0, Some("foo/bar/baz")),
+      ("foo/bar/baz", 1, Some("bar/baz")),
+      ("foo/bar/baz", 2, Some("baz")),
+      ("foo/bar/baz", 3, Some(".")),
+      ("foo/bar/baz", 4, None),
+      (".", 0, Some(".")),
+      (".", 1, None),
+      ("../foo", 0, Some("../foo")),
+      ("../foo", 1, None),
+    ] {
+      assert_eq!(
+        RelativePathBuf::new(path)
+          .unwrap()
+          .strip_components(n)
+          .as_ref()
+          .map(RelativePathBuf::as_os_str),
+        want.map(OsStr::new),
+        "{} {}",
+        path,
+        n
+      );
+    }
+  }
+
+  #[test]
+  fn paths_are_resolved_against_base() {
+    let path = RelativePathBuf::new("../foo").unwrap();
+    assert_eq!(path.to_path("/srv/www"), Path::new("/srv/foo"));
+    assert_eq!(path.to_path("bar"), Path::new("foo"));
+  }
+}
@@ -0,0 +1,100 @@
+use alloc::string::String;
+
+/// Remove `.` and `..` segments from the path of a URL, using the algorithm
+/// in section 5.2.4 of RFC 3986. Unlike path cleaning, empty segments are
+/// kept, percent-encoded characters are left untouched, so `%2E%2E` is not a
+/// `..` segment, and a trailing `.` or `..` segment leaves a trailing `/`.
+/// `..` segments never ascend above the start of the path, so the result
+/// never contains `.` or `..` segments.
+///
+/// ```
+/// use lexiclean::remove_dot_segments;
+///
+/// assert_eq!(remove_dot_segments("/a/b/c/./../../g"), "/a/g");
+/// assert_eq!(remove_dot_segments("/a/b/.."), "/a/");
+/// assert_eq!(remove_dot_segments("/../%2E%2E/x"), "/%2E%2E/x");
+/// ```
+pub fn remove_dot_segments(path: &str) -> String {
+  let mut input = path;
+  let mut output = String::with_capacity(path.len());
+
+  while !input.is_empty() {
+    if let Some(rest) = input
+      .strip_prefix("../")
+      .or_else(|| input.strip_prefix("./"))
+    {
+      input = rest;
+    } else if input.starts_with("/./") {
+      input = &input[2..];
+    } else if input == "/." {
+      input = "/";
+    } else if input.starts_with("/../") || input == "/.." {
+      input = &input[3..];
+      if input.is_empty() {
+        input = "/";
+      }
+      output.truncate(output.rfind('/').unwrap_or(0));
+    } else if input == "." || input == ".." {
+      input = "";
+    } else {
+      let start = usize::from(input.starts_with('/'));
+      let end = input[start..].find('/').map_or(input.len(), |i| i + start);
+      output.push_str(&input[..end]);
+      input = &input[end..];
+    }
+  }
+
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[track_caller]
+  fn case(path: &str, expected: &str) {
+    assert_eq!(remove_dot_segments(path), expected, "{}", path);
+  }
+
+  #[test]
+  fn rfc_examples() {
+    case("/a/b/c/./../../g", "/a/g");
+    case("mid/content=5/../6", "mid/6");
+  }
+
+  #[test]
+  fn dot_segments_are_removed() {
+    case("", "");
+    case("/", "/");
+    case(".", "");
+    case("..", "");
+    case("./", "");
+    case("../", "");
+    case("/.", "/");
+    case("/..", "/");
+    case("/./", "/");
+    case("/../", "/");
+    case("/../../g", "/g");
+    case("../g", "g");
+    case("./g", "g");
+    case("g/.", "g/");
+    case("g/..", "/");
+    case("/a/b/..", "/a/");
+    case("/a/b/../", "/a/");
+    case("/a/./b/", "/a/b/");
+  }
+
+  #[test]
+  fn other_segments_are_kept() {
+    case("/a//b", "/a//b");
+    case("//a/../b", "//b");
+    case("/a/.../b", "/a/.../b");
+    case("/a/.b/c..", "/a/.b/c..");
+    case("/a/%2E/%2e%2E/b", "/a/%2E/%2e%2E/b");
+    case(r"/a\..\b", r"/a\..\b");
+    case("é/b", "é/b");
+    case("../é", "é");
+    case("/é/../ü", "/ü");
+    case("a/é/..", "a/");
+  }
+}
@@ -0,0 +1,133 @@
+use {
+  crate::{check_nul, clean_components, RelativePathBuf},
+  std::{
+    fmt::{self, Display, Formatter},
+    path::{Component, Path, PathBuf},
+  },
+};
+
+/// Error returned by `sanitize_entry` if an archive entry name is unsafe to
+/// extract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryError {
+  /// The name is absolute, like `/etc/passwd`.
+  Absolute,
+  /// The name would ascend above the extraction directory, like `../foo`.
+  Escape,
+  /// The name contains a NUL byte.
+  Nul,
+  /// The name has a Windows prefix, like `C:` or `\\server\share`.
+  Prefix,
+}
+
+impl Display for EntryError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Absolute => write!(f, "archive entry name is absolute"),
+      Self::Escape => write!(f, "archive entry name ascends above its root"),
+      Self::Nul => write!(f, "archive entry name contains a NUL byte"),
+      Self::Prefix => write!(f, "archive entry name has a prefix"),
+    }
+  }
+}
+
+impl std::error::Error for EntryError {}
+
+/// Clean the archive entry name `name` into a relative path which is safe
+/// to join onto the extraction directory, preventing zip-slip.
+///
+/// Fails if `name` is absolute, has a Windows prefix, contains a NUL byte,
+/// or has a `..` which would ascend above the extraction directory after
+/// cleaning. `..` components which stay inside the name, like in
+/// `foo/../bar`, are cleaned away. Names are parsed with the separators of
+/// the current platform, so on Windows, `\` separates components, and on
+/// Unix, `..\foo` is a single, harmless, file name. The check is purely
+/// lexical, so symlinks created by earlier entries must be handled by the
+/// extractor.
+///
+/// ```
+/// use lexiclean::{sanitize_entry, EntryError};
+///
+/// assert_eq!(sanitize_entry("docs/./intro.md").unwrap().as_os_str(), "docs/intro.md");
+///
+/// assert_eq!(sanitize_entry("/etc/passwd"), Err(EntryError::Absolute));
+/// assert_eq!(sanitize_entry("docs/../../.bashrc"), Err(EntryError::Escape));
+/// ```
+pub fn sanitize_entry(name: impl AsRef<Path>) -> Result<RelativePathBuf, EntryError> {
+  let name = name.as_ref();
+
+  match name.components().next() {
+    Some(Component::Prefix(_)) => return Err(EntryError::Prefix),
+    Some(Component::RootDir) => return Err(EntryError::Absolute),
+    _ => {}
+  }
+
+  check_nul(name).map_err(|_| EntryError::Nul)?;
+
+  let cleaned = clean_components(name.components());
+
+  if cleaned.first() == Some(&Component::ParentDir) {
+    return Err(EntryError::Escape);
+  }
+
+  Ok(
+    RelativePathBuf::new(cleaned.into_iter().collect::<PathBuf>())
+      .unwrap_or_else(|error| unreachable!("cleaned entry name is not relative: {}", error)),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn safe_names_are_cleaned() {
+    for (name, want) in [
+      ("foo", "foo"),
+      ("foo/bar/", "foo/bar"),
+      ("./foo//bar", "foo/bar"),
+      ("foo/../bar", "bar"),
+      ("foo/..", "."),
+      ("./", "."),
+      ("", "."),
+    ] {
+      assert_eq!(sanitize_entry(name).unwrap().as_os_str(), want, "{}", name);
+    }
+  }
+
+  #[test]
+  fn unsafe_names_are_rejected() {
+    for (name, want) in [
+      ("/etc/passwd", EntryError::Absolute),
+      ("/", EntryError::Absolute),
+      ("..", EntryError::Escape),
+      ("../foo", EntryError::Escape),
+      ("foo/../../bar", EntryError::Escape),
+      ("./../foo", EntryError::Escape),
+      ("foo\0bar", EntryError::Nul),
+    ] {
+      assert_eq!(sanitize_entry(name), Err(want), "{}", name);
+    }
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn backslashes_are_not_separators_on_unix() {
+    assert_eq!(sanitize_entry(r"..\foo").unwrap().as_os_str(), r"..\foo");
+    assert_eq!(sanitize_entry("C:/foo").unwrap().as_os_str(), "C:/foo");
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn prefixed_names_are_rejected() {
+    for (name, want) in [
+      (r"C:foo", EntryError::Prefix),
+      (r"C:\foo", EntryError::Prefix),
+      (r"\\server\share\foo", EntryError::Prefix),
+      (r"\foo", EntryError::Absolute),
+      (r"foo\..\..\bar", EntryError::Escape),
+    ] {
+      assert_eq!(sanitize_entry(name), Err(want), "{}", name);
+    }
+  }
+}
@@ -0,0 +1,110 @@
+use crate::is_reserved_name;
+
+/// What `sanitize_filename` does with characters that are unsafe in file
+/// names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Replacement {
+  /// Remove unsafe characters.
+  Remove,
+  /// Replace each unsafe character with a character, which is removed
+  /// instead if it is itself unsafe.
+  Char(char),
+}
+
+/// Clean the single file name `name` so it is safe to use on every platform,
+/// for example for a name supplied with an upload, returning `None` if
+/// nothing usable remains, like for `..`.
+///
+/// Separators, NUL and other control characters, and the characters Windows
+/// forbids in file names, `<>:"|?*`, are handled according to
+/// `replacement`. Trailing dots and spaces, which Windows silently removes,
+/// are removed, and a Windows reserved device name, as determined by
+/// `is_reserved_name`, is prefixed with `_`, so `con.txt` becomes
+/// `_con.txt`.
+///
+/// ```
+/// use lexiclean::{sanitize_filename, Replacement};
+///
+/// assert_eq!(
+///   sanitize_filename("../etc/passwd", Replacement::Char('_')).unwrap(),
+///   ".._etc_passwd",
+/// );
+/// assert_eq!(sanitize_filename("report?.pdf", Replacement::Remove).unwrap(), "report.pdf");
+/// assert_eq!(sanitize_filename("..", Replacement::Remove), None);
+/// ```
+pub fn sanitize_filename(name: &str, replacement: Replacement) -> Option<String> {
+  let replacement = match replacement {
+    Replacement::Char(c) if !is_unsafe(c) => Some(c),
+    _ => None,
+  };
+
+  let mut sanitized = name
+    .chars()
+    .filter_map(|c| if is_unsafe(c) { replacement } else { Some(c) })
+    .collect::<String>();
+
+  sanitized.truncate(sanitized.trim_end_matches(['.', ' ']).len());
+
+  if sanitized.is_empty() {
+    return None;
+  }
+
+  if is_reserved_name(&sanitized) {
+    sanitized.insert(0, '_');
+  }
+
+  Some(sanitized)
+}
+
+fn is_unsafe(c: char) -> bool {
+  c.is_control() || r#"<>:"/\|?*"#.contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[track_caller]
+  fn case(name: &str, replacement: Replacement, want: Option<&str>) {
+    assert_eq!(
+      sanitize_filename(name, replacement).as_deref(),
+      want,
+      "{:?}",
+      name
+    );
+  }
+
+  #[test]
+  fn safe_names_are_unchanged() {
+    for name in ["foo", "foo.txt", ".bashrc", "..foo", "файл", "a b"] {
+      case(name, Replacement::Remove, Some(name));
+    }
+  }
+
+  #[test]
+  fn unsafe_characters_are_replaced() {
+    case("a/b\\c", Replacement::Char('_'), Some("a_b_c"));
+    case("a/b\\c", Replacement::Remove, Some("abc"));
+    case("a\0b\nc\u{7f}", Replacement::Char('-'), Some("a-b-c-"));
+    case(r#"<>:"|?*"#, Replacement::Char('_'), Some("_______"));
+    case(r#"<>:"|?*"#, Replacement::Remove, None);
+    case("a:b", Replacement::Char('?'), Some("ab"));
+  }
+
+  #[test]
+  fn trailing_dots_and_spaces_are_removed() {
+    case("foo. . ", Replacement::Remove, Some("foo"));
+    case(".", Replacement::Remove, None);
+    case("..", Replacement::Char('_'), None);
+    case("", Replacement::Remove, None);
+    case("foo/", Replacement::Char('.'), Some("foo"));
+  }
+
+  #[test]
+  fn reserved_names_are_prefixed() {
+    case("con", Replacement::Remove, Some("_con"));
+    case("NUL.txt", Replacement::Remove, Some("_NUL.txt"));
+    case("aux.", Replacement::Remove, Some("_aux"));
+    case("auxiliary", Replacement::Remove, Some("auxiliary"));
+  }
+}
@@ -0,0 +1,15 @@
+/// Separator to use in cleaned paths, with `Lexicleaner::separator`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Separator {
+  Backslash,
+  Slash,
+}
+
+impl Separator {
+  pub(crate) fn byte(self) -> u8 {
+    match self {
+      Self::Backslash => b'\\',
+      Self::Slash => b'/',
+    }
+  }
+}
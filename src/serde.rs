@@ -0,0 +1,362 @@
+//! `serde` support, available with the `serde` feature.
+//!
+//! `CleanPathBuf`, `RelativePathBuf`, and `AbsolutePathBuf` implement
+//! `Serialize` and `Deserialize`. For fields which must remain `PathBuf`s, the
+//! `clean`, `clean_relative`, and `clean_absolute` modules can be used with
+//! `#[serde(with = "...")]` to clean and validate paths like those types. Each
+//! also has a `serialize_portable` function, for use with
+//! `#[serde(serialize_with = "...")]`, which serializes paths with `/`
+//! separators, even on Windows.
+//!
+//! ```
+//! use {serde::Deserialize, std::path::PathBuf};
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!   #[serde(with = "lexiclean::serde::clean_relative")]
+//!   assets: PathBuf,
+//! }
+//!
+//! let config = serde_json::from_str::<Config>(r#"{"assets": "static/./img/"}"#).unwrap();
+//! assert_eq!(config.assets, PathBuf::from("static").join("img"));
+//!
+//! assert!(serde_json::from_str::<Config>(r#"{"assets": "/etc"}"#).is_err());
+//! ```
+
+use {
+  crate::{AbsolutePathBuf, CleanPathBuf, Lexicleaner, RelativePathBuf, Separator},
+  ::serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer},
+  std::{
+    convert::TryFrom,
+    path::{Path, PathBuf},
+  },
+};
+
+/// Serialized as a path. Deserialization cleans the path, and fails if it
+/// contains a NUL byte.
+impl Serialize for CleanPathBuf {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.as_path().serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for CleanPathBuf {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let path = PathBuf::deserialize(deserializer)?;
+    Self::try_from(path.as_path()).map_err(D::Error::custom)
+  }
+}
+
+/// Serialized as a path. Deserialization fails like `RelativePathBuf::new`.
+impl Serialize for RelativePathBuf {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    Path::new(self.as_os_str()).serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for RelativePathBuf {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Self::new(PathBuf::deserialize(deserializer)?).map_err(D::Error::custom)
+  }
+}
+
+/// Serialized as a path. Deserialization fails like `AbsolutePathBuf::new`.
+impl Serialize for AbsolutePathBuf {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.as_path().serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for AbsolutePathBuf {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Self::new(PathBuf::deserialize(deserializer)?).map_err(D::Error::custom)
+  }
+}
+
+/// Clean paths like `CleanPathBuf`, failing if they contain a NUL byte.
+pub mod clean {
+  use super::*;
+
+  pub fn serialize<S: Serializer>(
+    path: impl AsRef<Path>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    CleanPathBuf::try_from(path.as_ref())
+      .map_err(S::Error::custom)?
+      .serialize(serializer)
+  }
+
+  pub fn serialize_portable<S: Serializer>(
+    path: impl AsRef<Path>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    let path = CleanPathBuf::try_from(path.as_ref()).map_err(S::Error::custom)?;
+    portable(&path).serialize(serializer)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+    CleanPathBuf::deserialize(deserializer).map(CleanPathBuf::into_path_buf)
+  }
+}
+
+/// Clean paths like `RelativePathBuf`, failing if they are absolute, have a
+/// prefix, or contain a NUL byte.
+pub mod clean_relative {
+  use super::*;
+
+  pub fn serialize<S: Serializer>(
+    path: impl AsRef<Path>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    RelativePathBuf::new(path)
+      .map_err(S::Error::custom)?
+      .serialize(serializer)
+  }
+
+  pub fn serialize_portable<S: Serializer>(
+    path: impl AsRef<Path>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    let path = RelativePathBuf::new(path).map_err(S::Error::custom)?;
+    portable(path.as_os_str()).serialize(serializer)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+    RelativePathBuf::deserialize(deserializer).map(|path| path.as_os_str().into())
+  }
+}
+
+/// Clean paths like `AbsolutePathBuf`, failing if they are relative, ascend
+/// above their root, or contain a NUL byte.
+pub mod clean_absolute {
+  use super::*;
+
+  pub fn serialize<S: Serializer>(
+    path: impl AsRef<Path>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    AbsolutePathBuf::new(path)
+      .map_err(S::Error::custom)?
+      .serialize(serializer)
+  }
+
+  pub fn serialize_portable<S: Serializer>(
+    path: impl AsRef<Path>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    let path = AbsolutePathBuf::new(path).map_err(S::Error::custom)?;
+    portable(&path).serialize(serializer)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+    AbsolutePathBuf::deserialize(deserializer).map(AbsolutePathBuf::into_path_buf)
+  }
+}
+
+/// Replace separators in the clean path `path` with `/`, which only changes
+/// paths on Windows.
+fn portable(path: impl AsRef<Path>) -> PathBuf {
+  Lexicleaner::new().separator(Separator::Slash).clean(path)
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, serde_json::json};
+
+  #[test]
+  fn clean_paths_round_trip() {
+    let path = serde_json::from_value::<CleanPathBuf>(json!("foo/./bar/../baz")).unwrap();
+    assert_eq!(path, Path::new("foo/baz"));
+    assert_eq!(serde_json::to_value(&path).unwrap(), json!("foo/baz"));
+    assert_eq!(
+      serde_json::from_value::<CleanPathBuf>(serde_json::to_value(&path).unwrap()).unwrap(),
+      path,
+    );
+  }
+
+  #[test]
+  fn relative_paths_round_trip() {
+    let path = serde_json::from_value::<RelativePathBuf>(json!("a/../../b/")).unwrap();
+    assert_eq!(path, RelativePathBuf::new("../b").unwrap());
+    assert_eq!(serde_json::to_value(&path).unwrap(), json!("../b"));
+    assert_eq!(
+      serde_json::from_value::<RelativePathBuf>(json!("")).unwrap(),
+      RelativePathBuf::new(".").unwrap(),
+    );
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn absolute_paths_round_trip() {
+    let path = serde_json::from_value::<AbsolutePathBuf>(json!("/opt/./app/")).unwrap();
+    assert_eq!(path, Path::new("/opt/app"));
+    assert_eq!(serde_json::to_value(&path).unwrap(), json!("/opt/app"));
+  }
+
+  #[cfg(windows)]
+  #[test]
+  fn absolute_paths_round_trip() {
+    let path = serde_json::from_value::<AbsolutePathBuf>(json!(r"C:\opt\.\app\")).unwrap();
+    assert_eq!(path, Path::new(r"C:\opt\app"));
+    assert_eq!(serde_json::to_value(&path).unwrap(), json!(r"C:\opt\app"));
+  }
+
+  #[test]
+  fn invalid_paths_are_rejected() {
+    #[track_caller]
+    fn case<T: for<'de> Deserialize<'de>>(path: &str, message: &str) {
+      let error = serde_json::from_value::<T>(json!(path)).err().unwrap();
+      assert_eq!(error.to_string(), message);
+    }
+
+    case::<CleanPathBuf>("foo\0bar", "path contains a NUL byte");
+    case::<RelativePathBuf>("foo\0bar", "path contains a NUL byte");
+    case::<AbsolutePathBuf>("foo\0bar", "path is relative");
+    case::<AbsolutePathBuf>("foo/bar", "path is relative");
+
+    #[cfg(unix)]
+    {
+      case::<RelativePathBuf>("/etc/passwd", "path is absolute");
+      case::<AbsolutePathBuf>("/..", "path ascends above its root");
+      case::<AbsolutePathBuf>("/foo\0bar", "path contains a NUL byte");
+    }
+
+    #[cfg(windows)]
+    {
+      case::<RelativePathBuf>(r"C:\Windows", "path is absolute");
+      case::<RelativePathBuf>(r"C:foo", "path is absolute");
+      case::<AbsolutePathBuf>(r"C:\..", "path ascends above its root");
+      case::<AbsolutePathBuf>("C:\\foo\0bar", "path contains a NUL byte");
+    }
+  }
+
+  #[test]
+  fn non_strings_are_rejected() {
+    assert!(serde_json::from_value::<CleanPathBuf>(json!(1)).is_err());
+    assert!(serde_json::from_value::<RelativePathBuf>(json!(["foo"])).is_err());
+  }
+
+  #[derive(Debug, PartialEq, Deserialize, Serialize)]
+  struct Config {
+    #[serde(with = "clean")]
+    cache: PathBuf,
+    #[serde(with = "clean_relative")]
+    assets: PathBuf,
+    #[serde(
+      serialize_with = "clean_relative::serialize_portable",
+      deserialize_with = "clean_relative::deserialize"
+    )]
+    manifest: PathBuf,
+    #[serde(with = "clean_absolute")]
+    root: PathBuf,
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn path_buf_fields_are_cleaned() {
+    let config = serde_json::from_value::<Config>(json!({
+      "cache": "../cache/./",
+      "assets": "static//img/..",
+      "manifest": "./pkg/Cargo.toml",
+      "root": "/srv/./www/",
+    }))
+    .unwrap();
+
+    assert_eq!(
+      config,
+      Config {
+        cache: "../cache".into(),
+        assets: "static".into(),
+        manifest: "pkg/Cargo.toml".into(),
+        root: "/srv/www".into(),
+      },
+    );
+
+    let unclean = Config {
+      cache: "a/../b".into(),
+      assets: "c/.".into(),
+      manifest: "d//e".into(),
+      root: "/f/".into(),
+    };
+
+    assert_eq!(
+      serde_json::to_value(&unclean).unwrap(),
+      json!({"cache": "b", "assets": "c", "manifest": "d/e", "root": "/f"}),
+    );
+  }
+
+  #[cfg(windows)]
+  #[test]
+  fn path_buf_fields_are_cleaned() {
+    let config = serde_json::from_value::<Config>(json!({
+      "cache": r"..\cache\.",
+      "assets": "static//img/..",
+      "manifest": r".\pkg\Cargo.toml",
+      "root": r"C:\srv\.\www",
+    }))
+    .unwrap();
+
+    assert_eq!(
+      config,
+      Config {
+        cache: r"..\cache".into(),
+        assets: "static".into(),
+        manifest: r"pkg\Cargo.toml".into(),
+        root: r"C:\srv\www".into(),
+      },
+    );
+
+    assert_eq!(
+      serde_json::to_value(&config).unwrap(),
+      json!({
+        "cache": r"..\cache",
+        "assets": "static",
+        "manifest": "pkg/Cargo.toml",
+        "root": r"C:\srv\www",
+      }),
+    );
+  }
+
+  #[test]
+  fn invalid_path_buf_fields_are_rejected() {
+    let mut config = json!({
+      "cache": "cache",
+      "assets": "assets",
+      "manifest": "Cargo.toml",
+      "root": if cfg!(windows) { r"C:\srv" } else { "/srv" },
+    });
+    assert!(serde_json::from_value::<Config>(config.clone()).is_ok());
+
+    config["assets"] = json!(if cfg!(windows) {
+      r"C:ssets"
+    } else {
+      "/assets"
+    });
+    assert_eq!(
+      serde_json::from_value::<Config>(config.clone())
+        .unwrap_err()
+        .to_string(),
+      "path is absolute",
+    );
+
+    config["assets"] = json!("assets");
+    config["root"] = json!("srv");
+    assert_eq!(
+      serde_json::from_value::<Config>(config)
+        .unwrap_err()
+        .to_string(),
+      "path is relative",
+    );
+
+    let invalid = Config {
+      cache: "cache".into(),
+      assets: "assets".into(),
+      manifest: "Cargo.toml".into(),
+      root: "srv".into(),
+    };
+    assert_eq!(
+      serde_json::to_value(&invalid).unwrap_err().to_string(),
+      "path is relative",
+    );
+  }
+}
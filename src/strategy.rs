@@ -0,0 +1,131 @@
+//! `proptest` strategies which generate adversarial paths, for property
+//! testing code which consumes cleaned paths, available with the
+//! `test-support` feature.
+//!
+//! ```
+//! use {
+//!   lexiclean::{lexiclean_posix, strategy},
+//!   proptest::{prop_assert_eq, proptest},
+//! };
+//!
+//! proptest!(|(path in strategy::posix_path())| {
+//!   let cleaned = lexiclean_posix(&path);
+//!   prop_assert_eq!(lexiclean_posix(&cleaned), cleaned);
+//! });
+//! ```
+
+use {
+  alloc::{string::String, vec, vec::Vec},
+  proptest::{collection, prelude::*, sample::select},
+};
+
+/// Names which are easily mishandled: `.` and `..` and their near misses,
+/// names with leading `-`, embedded NUL bytes, non-ASCII characters, and
+/// names which look like Windows drives and streams.
+const NAMES: &[&str] = &[
+  "", ".", "..", "...", ". .", ".a", "a.", "a..", "a", "b", "-a", "--", "a\0", "\0", "é",
+  "e\u{301}", "C:", "a:b", "CON", "a ", " ",
+];
+
+/// Names, or long runs of `..`, which ascend many levels at once.
+fn name(separators: &'static [&'static str]) -> impl Strategy<Value = String> {
+  prop_oneof![
+    4 => select(NAMES).prop_map(String::from),
+    1 => (1..24usize, select(separators)).prop_map(|(n, separator)| vec![".."; n].join(separator)),
+  ]
+}
+
+fn path(
+  roots: &'static [&'static str],
+  separators: &'static [&'static str],
+) -> impl Strategy<Value = String> {
+  (
+    select(roots),
+    collection::vec((name(separators), select(separators)), 0..12),
+  )
+    .prop_map(|(root, components)| {
+      let mut path = String::from(root);
+      for (name, separator) in components {
+        path.push_str(&name);
+        path.push_str(separator);
+      }
+      path
+    })
+}
+
+/// POSIX paths, with runs of separators, `.` and `..` components, long runs
+/// of `..`, backslashes, and NUL bytes.
+pub fn posix_path() -> impl Strategy<Value = String> {
+  path(
+    &["", "", "/", "//", "///"],
+    &["/", "/", "//", "///", "/./", "/../", r"\", r"/\/"],
+  )
+}
+
+/// Windows paths, with drive, UNC, device, and verbatim prefixes, mixed
+/// `/` and `\` separators, `.` and `..` components, long runs of `..`, and
+/// NUL bytes.
+pub fn windows_path() -> impl Strategy<Value = String> {
+  path(
+    &[
+      "",
+      r"\",
+      "/",
+      "C:",
+      "z:",
+      r"C:\",
+      "c:/",
+      r"\\server\share",
+      r"\\server\share\",
+      "//server/share/",
+      r"\\server",
+      r"\\.\pipe\",
+      r"\\.\COM1",
+      "//./",
+      r"\\?\C:\",
+      r"\\?\C:",
+      r"\\?\UNC\server\share\",
+      r"\\?\GLOBALROOT\",
+      "//?/",
+    ],
+    &[r"\", "/", r"\\", r"/\", r"\.\", r"\..\", "//"],
+  )
+}
+
+/// POSIX paths as bytes, which may not be valid UTF-8, for testing code
+/// which handles Unix paths as bytes.
+pub fn posix_path_bytes() -> impl Strategy<Value = Vec<u8>> {
+  collection::vec(
+    prop_oneof![
+      4 => posix_path().prop_map(String::into_bytes),
+      1 => collection::vec(
+        prop_oneof![Just(b'/'), Just(b'.'), Just(0xFF), Just(0xC0), any::<u8>()],
+        1..8,
+      ),
+    ],
+    0..4,
+  )
+  .prop_map(|parts| parts.concat())
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::lexiclean_windows};
+
+  proptest! {
+    #[test]
+    fn windows_cleaning_is_idempotent(path in windows_path()) {
+      let cleaned = lexiclean_windows(&path);
+      prop_assert_eq!(lexiclean_windows(&cleaned), cleaned);
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "std"))]
+    fn byte_cleaning_is_idempotent(path in posix_path_bytes()) {
+      use {crate::Lexiclean, std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path}};
+
+      let cleaned = Path::new(OsStr::from_bytes(&path)).lexiclean();
+      prop_assert_eq!((&cleaned).lexiclean(), cleaned);
+    }
+  }
+}
@@ -0,0 +1,107 @@
+use std::{
+  ffi::OsStr,
+  path::{self, Component, Path, PathBuf},
+};
+
+/// Split an NTFS alternate data stream suffix from the final component of
+/// `path`, returning the path of the file and the stream name, which may
+/// include a stream type. `file.txt:Zone.Identifier:$DATA` is split into
+/// `file.txt` and `Zone.Identifier:$DATA`. Returns `None` if the final
+/// component has no stream suffix.
+///
+/// Splitting off the stream before cleaning, and reattaching it with
+/// `join_stream` afterwards, ensures that the stream name is never treated as
+/// part of a file name:
+///
+/// ```
+/// use lexiclean::{join_stream, split_stream, Lexiclean};
+/// use std::path::Path;
+///
+/// let (file, stream) = split_stream(Path::new("foo/../file.txt:Zone.Identifier")).unwrap();
+///
+/// assert_eq!(
+///   join_stream(file.lexiclean(), stream),
+///   Path::new("file.txt:Zone.Identifier"),
+/// );
+/// ```
+pub fn split_stream(path: &Path) -> Option<(&Path, &OsStr)> {
+  let bytes = path.as_os_str().as_encoded_bytes();
+
+  let prefix = match path.components().next() {
+    Some(Component::Prefix(prefix)) => prefix.as_os_str().len(),
+    _ => 0,
+  };
+
+  let name = bytes[prefix..]
+    .iter()
+    .rposition(|&byte| path::is_separator(byte.into()))
+    .map_or(prefix, |i| prefix + i + 1);
+
+  let colon = name + bytes[name..].iter().position(|&byte| byte == b':')?;
+
+  // SAFETY: Both halves are split at an ASCII `:`, and `bytes` came from
+  // `OsStr::as_encoded_bytes`.
+  unsafe {
+    Some((
+      Path::new(OsStr::from_encoded_bytes_unchecked(&bytes[..colon])),
+      OsStr::from_encoded_bytes_unchecked(&bytes[colon + 1..]),
+    ))
+  }
+}
+
+/// Reattach an alternate data stream suffix split off by `split_stream`.
+pub fn join_stream(path: impl Into<PathBuf>, stream: impl AsRef<OsStr>) -> PathBuf {
+  let mut path = path.into();
+  let os_string = path.as_mut_os_string();
+  os_string.push(":");
+  os_string.push(stream);
+  path
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[track_caller]
+  fn case(path: &str, want: Option<(&str, &str)>) {
+    assert_eq!(
+      split_stream(Path::new(path)),
+      want.map(|(file, stream)| (Path::new(file), OsStr::new(stream))),
+    );
+
+    if let Some((file, stream)) = want {
+      assert_eq!(join_stream(file, stream), Path::new(path));
+    }
+  }
+
+  #[test]
+  fn streams_are_split() {
+    case(
+      "file.txt:Zone.Identifier",
+      Some(("file.txt", "Zone.Identifier")),
+    );
+    case(
+      "foo/file.txt:stream:$DATA",
+      Some(("foo/file.txt", "stream:$DATA")),
+    );
+    case("foo/file.txt::$DATA", Some(("foo/file.txt", ":$DATA")));
+    case("foo/bar:", Some(("foo/bar", "")));
+  }
+
+  #[test]
+  fn only_final_component_is_split() {
+    case("file.txt", None);
+    case("foo:bar/baz", None);
+    case("foo/bar/", None);
+    case("", None);
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn prefix_is_not_split() {
+    case(r"C:", None);
+    case(r"C:\foo", None);
+    case(r"C:foo:stream", Some((r"C:foo", "stream")));
+    case(r"C:\foo:stream", Some((r"C:\foo", "stream")));
+  }
+}
@@ -0,0 +1,145 @@
+use {
+  crate::{PurePosixPath, PureWindowsPath},
+  typed_path::{
+    Utf8TypedPath, Utf8TypedPathBuf, Utf8UnixPath, Utf8UnixPathBuf, Utf8WindowsPath,
+    Utf8WindowsPathBuf,
+  },
+};
+
+/// Cleaning for the UTF-8 paths of the `typed-path` crate, which are cleaned
+/// according to their flavor, like `PurePosixPath` and `PureWindowsPath`,
+/// regardless of the host platform.
+///
+/// This is a separate trait from `Lexiclean`, which requires
+/// `AsRef<std::path::Path>`, since `typed-path` paths are not host paths.
+///
+/// ```
+/// use lexiclean::LexicleanTyped;
+/// use typed_path::{Utf8UnixPath, Utf8WindowsPath};
+///
+/// assert_eq!(Utf8UnixPath::new("/foo/../bar/.").lexiclean().as_str(), "/bar");
+/// assert_eq!(
+///   Utf8WindowsPath::new(r"C:/foo\..\bar").lexiclean().as_str(),
+///   r"C:\bar",
+/// );
+/// ```
+pub trait LexicleanTyped {
+  /// The type of the cleaned path, the owned path of the same flavor.
+  type Output;
+
+  fn lexiclean(self) -> Self::Output;
+}
+
+impl LexicleanTyped for &Utf8UnixPath {
+  type Output = Utf8UnixPathBuf;
+
+  fn lexiclean(self) -> Utf8UnixPathBuf {
+    PurePosixPath::from(self).lexiclean().into()
+  }
+}
+
+impl LexicleanTyped for &Utf8WindowsPath {
+  type Output = Utf8WindowsPathBuf;
+
+  fn lexiclean(self) -> Utf8WindowsPathBuf {
+    PureWindowsPath::from(self).lexiclean().into()
+  }
+}
+
+impl LexicleanTyped for Utf8TypedPath<'_> {
+  type Output = Utf8TypedPathBuf;
+
+  fn lexiclean(self) -> Utf8TypedPathBuf {
+    match self {
+      Self::Unix(path) => Utf8TypedPathBuf::Unix(path.lexiclean()),
+      Self::Windows(path) => Utf8TypedPathBuf::Windows(path.lexiclean()),
+    }
+  }
+}
+
+impl LexicleanTyped for &Utf8TypedPathBuf {
+  type Output = Utf8TypedPathBuf;
+
+  fn lexiclean(self) -> Utf8TypedPathBuf {
+    self.to_path().lexiclean()
+  }
+}
+
+impl From<&Utf8UnixPath> for PurePosixPath {
+  fn from(path: &Utf8UnixPath) -> Self {
+    Self::new(path.as_str())
+  }
+}
+
+impl From<&Utf8WindowsPath> for PureWindowsPath {
+  fn from(path: &Utf8WindowsPath) -> Self {
+    Self::new(path.as_str())
+  }
+}
+
+impl From<PurePosixPath> for Utf8UnixPathBuf {
+  fn from(path: PurePosixPath) -> Self {
+    path.into_string().into()
+  }
+}
+
+impl From<PureWindowsPath> for Utf8WindowsPathBuf {
+  fn from(path: PureWindowsPath) -> Self {
+    path.into_string().into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unix_paths_are_cleaned() {
+    for (path, want) in [
+      ("", "."),
+      ("foo//./bar/..", "foo"),
+      ("/../foo/", "/foo"),
+      (r"foo\..\bar", r"foo\..\bar"),
+    ] {
+      assert_eq!(
+        Utf8UnixPath::new(path).lexiclean().as_str(),
+        want,
+        "{}",
+        path
+      );
+    }
+  }
+
+  #[test]
+  fn windows_paths_are_cleaned() {
+    for (path, want) in [
+      ("", "."),
+      (r"foo/.\bar\..", "foo"),
+      (r"C:\..\foo", r"C:\foo"),
+      (r"\\server\share\..", r"\\server\share\"),
+      (r"\\?\C:\foo\..", r"\\?\C:\foo\.."),
+    ] {
+      assert_eq!(
+        Utf8WindowsPath::new(path).lexiclean().as_str(),
+        want,
+        "{}",
+        path
+      );
+    }
+  }
+
+  #[test]
+  fn typed_paths_are_cleaned_by_flavor() {
+    let unix = Utf8TypedPath::unix(r"a\..\b/../c");
+    assert_eq!(unix.lexiclean(), Utf8TypedPathBuf::from_unix(r"c"));
+
+    let windows = Utf8TypedPath::windows(r"a\..\b/../c");
+    assert_eq!(windows.lexiclean(), Utf8TypedPathBuf::from_windows("c"));
+
+    let buf = Utf8TypedPathBuf::from_windows("C:/a/./b");
+    assert_eq!(
+      (&buf).lexiclean(),
+      Utf8TypedPathBuf::from_windows(r"C:\a\b")
+    );
+  }
+}
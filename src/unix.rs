@@ -0,0 +1,100 @@
+use {
+  crate::lexiclean_to_buf::find_separator,
+  std::{
+    ffi::OsString,
+    mem,
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
+  },
+};
+
+/// Clean `path` into `out` like `Lexicleaner::new()`, scanning its bytes
+/// for separators instead of iterating over its components, which is
+/// possible since Unix paths have no prefixes.
+pub(crate) fn clean_into(path: &Path, out: &mut PathBuf) {
+  let mut bytes = mem::take(out).into_os_string().into_vec();
+  bytes.clear();
+  bytes.reserve(path.as_os_str().len() + 1);
+  bytes.extend_from_slice(path.as_os_str().as_bytes());
+  clean_in_place(&mut bytes);
+  *out = OsString::from_vec(bytes).into();
+}
+
+/// Clean the Unix path in `bytes` in place, like `Lexicleaner::new()`.
+pub(crate) fn clean_in_place(bytes: &mut Vec<u8>) {
+  let rooted = bytes.first() == Some(&b'/');
+
+  // Cleaned components are written to the front of the buffer, which never
+  // overtakes the component being read, since every component after the
+  // first is preceded by at least one separator.
+  let mut len = if rooted { 1 } else { 0 };
+  let mut normals = 0;
+  let mut start = 0;
+
+  while start < bytes.len() {
+    let end = find_separator(&bytes[start..]).map_or(bytes.len(), |i| start + i);
+
+    match &bytes[start..end] {
+      b"" | b"." => {}
+      b".." if normals > 0 => {
+        len = match bytes[..len].iter().rposition(|&byte| byte == b'/') {
+          Some(0) if rooted => 1,
+          Some(i) => i,
+          None => 0,
+        };
+        normals -= 1;
+      }
+      b".." if rooted => {}
+      component => {
+        if component != b".." {
+          normals += 1;
+        }
+
+        if len > usize::from(rooted) {
+          bytes[len] = b'/';
+          len += 1;
+        }
+
+        bytes.copy_within(start..end, len);
+        len += end - start;
+      }
+    }
+
+    start = end + 1;
+  }
+
+  bytes.truncate(len);
+
+  if bytes.is_empty() {
+    bytes.push(b'.');
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::Lexicleaner};
+
+  #[test]
+  fn bytes_are_cleaned_like_components() {
+    let mut paths = vec![String::new()];
+    let mut out = PathBuf::new();
+    let mut want = PathBuf::new();
+
+    for _ in 0..6 {
+      paths = paths
+        .iter()
+        .flat_map(|path| {
+          ["a", ".", "/"]
+            .iter()
+            .map(move |c| format!("{}{}", path, c))
+        })
+        .collect();
+
+      for path in &paths {
+        clean_into(Path::new(path), &mut out);
+        Lexicleaner::new().write_components(Path::new(path).components(), &mut want);
+        assert_eq!(out.as_os_str(), want.as_os_str(), "{}", path);
+      }
+    }
+  }
+}
@@ -0,0 +1,30 @@
+//! JavaScript functions for cleaning POSIX and Windows paths, exported with
+//! `wasm-bindgen` as `lexicleanPosix` and `lexicleanWindows`, which match
+//! `lexiclean_posix` and `lexiclean_windows`.
+
+use {alloc::string::String, wasm_bindgen::prelude::wasm_bindgen};
+
+/// Clean `path` like `lexiclean_posix`. Exported as `lexicleanPosix`.
+#[wasm_bindgen(js_name = lexicleanPosix)]
+pub fn lexiclean_posix(path: &str) -> String {
+  crate::lexiclean_posix(path)
+}
+
+/// Clean `path` like `lexiclean_windows`. Exported as `lexicleanWindows`.
+#[wasm_bindgen(js_name = lexicleanWindows)]
+pub fn lexiclean_windows(path: &str) -> String {
+  crate::lexiclean_windows(path)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn paths_are_cleaned_like_pure_paths() {
+    assert_eq!(lexiclean_posix(r"/foo/../C:\bar/."), r"/C:\bar");
+    assert_eq!(lexiclean_posix(""), ".");
+    assert_eq!(lexiclean_windows(r"C:/foo\..\bar"), r"C:\bar");
+    assert_eq!(lexiclean_windows(r"\\server\share\.."), r"\\server\share\");
+  }
+}
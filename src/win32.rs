@@ -0,0 +1,326 @@
+use std::{
+  ffi::{OsStr, OsString},
+  path::{Component, Path, PathBuf, Prefix},
+};
+
+/// Windows' `MAX_PATH`, the length limit for paths without a verbatim prefix,
+/// including a terminating NUL.
+pub(crate) const MAX_PATH: usize = 260;
+
+const RESERVED_NAMES: &[&str] = &[
+  "AUX", "CON", "CONIN$", "CONOUT$", "NUL", "PRN", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5",
+  "COM6", "COM7", "COM8", "COM9", "COM¹", "COM²", "COM³", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4",
+  "LPT5", "LPT6", "LPT7", "LPT8", "LPT9", "LPT¹", "LPT²", "LPT³",
+];
+
+/// Returns `true` if `name` is a Windows reserved device name, like `CON`,
+/// `NUL`, or `COM1`. Names are matched case-insensitively, and with an
+/// extension or stream suffix, so `nul.txt` and `Con:stream` are reserved,
+/// since Windows opens the device instead of a file with that name.
+///
+/// ```
+/// use lexiclean::is_reserved_name;
+///
+/// assert!(is_reserved_name("aux.tar.gz"));
+/// assert!(!is_reserved_name("auxiliary"));
+/// ```
+pub fn is_reserved_name(name: impl AsRef<OsStr>) -> bool {
+  let name = name.as_ref().as_encoded_bytes();
+
+  let mut stem = name
+    .split(|&byte| byte == b'.' || byte == b':')
+    .next()
+    .unwrap_or_default();
+
+  while let Some(rest) = stem.strip_suffix(b" ") {
+    stem = rest;
+  }
+
+  RESERVED_NAMES
+    .iter()
+    .any(|reserved| stem.eq_ignore_ascii_case(reserved.as_bytes()))
+}
+
+/// Convert a path with a verbatim disk or UNC prefix to the equivalent path
+/// without one, so `\\?\C:\foo` becomes `C:\foo`, and
+/// `\\?\UNC\server\share\foo` becomes `\\server\share\foo`. Returns `None` if
+/// `path` has no such prefix, or if the conversion would change which file
+/// the path refers to. Conversion is lossless, so `to_verbatim` returns the
+/// original path.
+pub fn from_verbatim(path: impl AsRef<Path>) -> Option<PathBuf> {
+  let path = path.as_ref();
+  let mut components = path.components();
+
+  let mut simplified = match components.next()? {
+    Component::Prefix(prefix) => match prefix.kind() {
+      Prefix::VerbatimDisk(letter) => PathBuf::from(format!("{}:", char::from(letter))),
+      Prefix::VerbatimUNC(server, share) => {
+        let mut unc = PathBuf::from(r"\\");
+        unc.as_mut_os_string().push(server);
+        unc.as_mut_os_string().push(r"\");
+        unc.as_mut_os_string().push(share);
+        unc
+      }
+      _ => return None,
+    },
+    _ => return None,
+  };
+
+  if components.next()? != Component::RootDir {
+    return None;
+  }
+
+  simplified.push(Component::RootDir);
+
+  for component in components {
+    match component {
+      Component::Normal(name) if is_portable_name(name) => simplified.push(name),
+      _ => return None,
+    }
+  }
+
+  if ends_with_separator(path) && !ends_with_separator(&simplified) {
+    simplified.as_mut_os_string().push(r"\");
+  }
+
+  if is_long(&simplified) {
+    return None;
+  }
+
+  Some(simplified)
+}
+
+/// Returns `true` if `path` is too long to be used without a verbatim prefix.
+pub(crate) fn is_long(path: &Path) -> bool {
+  utf16_len(path.as_os_str()) >= MAX_PATH
+}
+
+/// Convert an absolute disk or UNC path to the equivalent path with a
+/// verbatim prefix, so `C:\foo` becomes `\\?\C:\foo`, and
+/// `\\server\share\foo` becomes `\\?\UNC\server\share\foo`. Returns `None` if
+/// `path` is not such a path, or if the conversion would change which file
+/// the path refers to, for example because `path` contains `..`, or a
+/// component ending with a dot. Redundant separators are removed, but a
+/// trailing separator is kept, so `from_verbatim` returns the original path
+/// if it was clean.
+pub fn to_verbatim(path: impl AsRef<Path>) -> Option<PathBuf> {
+  let path = path.as_ref();
+  let mut components = path.components();
+
+  let mut verbatim = match components.next()? {
+    Component::Prefix(prefix) => match prefix.kind() {
+      Prefix::Disk(letter) => OsString::from(format!(r"\\?\{}:", char::from(letter))),
+      Prefix::UNC(server, share) => {
+        let mut verbatim = OsString::from(r"\\?\UNC\");
+        verbatim.push(server);
+        verbatim.push(r"\");
+        verbatim.push(share);
+        verbatim
+      }
+      _ => return None,
+    },
+    _ => return None,
+  };
+
+  if components.next()? != Component::RootDir {
+    return None;
+  }
+
+  verbatim.push(r"\");
+
+  for (i, component) in components.enumerate() {
+    match component {
+      Component::Normal(name) if is_portable_name(name) => {
+        if i > 0 {
+          verbatim.push(r"\");
+        }
+        verbatim.push(name);
+      }
+      _ => return None,
+    }
+  }
+
+  if ends_with_separator(path) && !ends_with_separator(Path::new(&verbatim)) {
+    verbatim.push(r"\");
+  }
+
+  Some(verbatim.into())
+}
+
+fn ends_with_separator(path: &Path) -> bool {
+  matches!(
+    path.as_os_str().as_encoded_bytes().last(),
+    Some(b'\\' | b'/')
+  )
+}
+
+/// The length of `s` in UTF-16 code units, as Windows measures path length.
+fn utf16_len(s: &OsStr) -> usize {
+  s.as_encoded_bytes()
+    .iter()
+    .map(|&byte| match byte {
+      0x80..=0xBF => 0,
+      0xF0..=0xFF => 2,
+      _ => 1,
+    })
+    .sum()
+}
+
+/// Returns `true` if `name` means the same thing with and without a verbatim
+/// prefix.
+fn is_portable_name(name: &OsStr) -> bool {
+  let bytes = name.as_encoded_bytes();
+
+  !bytes.is_empty()
+    && !bytes.ends_with(b".")
+    && !bytes.ends_with(b" ")
+    && !bytes
+      .iter()
+      .any(|&byte| byte < 0x20 || br#"<>:"/\|?*"#.contains(&byte))
+    && !is_reserved_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reserved_names() {
+    for name in [
+      "CON",
+      "con",
+      "Nul",
+      "COM1",
+      "lpt9",
+      "COM¹",
+      "NUL.txt",
+      "CON .tar.gz",
+      "AUX  ",
+    ] {
+      assert!(is_reserved_name(name), "{}", name);
+    }
+  }
+
+  #[test]
+  fn unreserved_names() {
+    for name in ["CONSOLE", "COM10", "foo.CON", "NULL", "", "LPT", "xCON"] {
+      assert!(!is_reserved_name(name), "{}", name);
+    }
+  }
+
+  #[test]
+  fn utf16_length() {
+    assert_eq!(utf16_len(OsStr::new("")), 0);
+    assert_eq!(utf16_len(OsStr::new("foo")), 3);
+    assert_eq!(utf16_len(OsStr::new("é")), 1);
+    assert_eq!(utf16_len(OsStr::new("€")), 1);
+    assert_eq!(utf16_len(OsStr::new("🦀")), 2);
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn paths_are_converted_to_verbatim() {
+    assert_eq!(
+      to_verbatim(Path::new(r"C:\foo\bar")).unwrap().as_os_str(),
+      r"\\?\C:\foo\bar",
+    );
+    assert_eq!(
+      to_verbatim(Path::new(r"\\server\share\foo"))
+        .unwrap()
+        .as_os_str(),
+      r"\\?\UNC\server\share\foo",
+    );
+    assert_eq!(
+      to_verbatim(Path::new(r"C:\")).unwrap().as_os_str(),
+      r"\\?\C:\"
+    );
+    assert_eq!(
+      to_verbatim(Path::new(r"C:\foo\")).unwrap().as_os_str(),
+      r"\\?\C:\foo\"
+    );
+
+    for path in [
+      r"C:foo",
+      r"foo\bar",
+      r"\foo",
+      r"C:\..",
+      r"C:\foo.",
+      r"\\?\C:\foo",
+    ] {
+      assert_eq!(to_verbatim(Path::new(path)), None, "{}", path);
+    }
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn verbatim_paths_are_simplified() {
+    assert_eq!(
+      from_verbatim(Path::new(r"\\?\C:\foo\bar"))
+        .unwrap()
+        .as_os_str(),
+      r"C:\foo\bar",
+    );
+    assert_eq!(
+      from_verbatim(Path::new(r"\\?\UNC\server\share\foo"))
+        .unwrap()
+        .as_os_str(),
+      r"\\server\share\foo",
+    );
+    assert_eq!(
+      from_verbatim(Path::new(r"\\?\C:\")).unwrap().as_os_str(),
+      r"C:\",
+    );
+  }
+
+  #[test]
+  fn paths_without_prefixes_are_not_converted() {
+    for path in ["/foo", "foo/bar", ""] {
+      assert_eq!(to_verbatim(path), None, "{}", path);
+      assert_eq!(from_verbatim(path), None, "{}", path);
+    }
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn verbatim_conversion_round_trips() {
+    for path in [
+      r"C:\",
+      r"C:\foo",
+      r"C:\foo\",
+      r"\\server\share\",
+      r"\\server\share\foo\bar",
+      r"\\server\share\foo\",
+    ] {
+      let verbatim = to_verbatim(path).unwrap();
+      assert_eq!(
+        from_verbatim(&verbatim).unwrap(),
+        Path::new(path),
+        "{}",
+        path
+      );
+    }
+  }
+
+  #[test]
+  #[cfg(windows)]
+  fn unsafe_verbatim_paths_are_not_simplified() {
+    for path in [
+      r"\\?\C:",
+      r"\\?\C:\foo\..\bar",
+      r"\\?\C:\foo\.",
+      r"\\?\C:\foo.",
+      r"\\?\C:\foo ",
+      r"\\?\C:\NUL",
+      r"\\?\C:\foo\com1.txt",
+      r"\\?\C:\foo/bar",
+      r"\\?\C:\foo:bar",
+      r"\\?\GLOBALROOT\Device",
+      r"C:\foo",
+    ] {
+      assert_eq!(from_verbatim(Path::new(path)), None, "{}", path);
+    }
+
+    let long = format!(r"\\?\C:\{}", "a".repeat(MAX_PATH));
+    assert_eq!(from_verbatim(Path::new(&long)), None);
+  }
+}
@@ -0,0 +1,78 @@
+use crate::{
+  cygwin::{mount_to_windows, windows_to_mount},
+  lexiclean_posix,
+};
+
+/// Convert a Windows path to the path of the same file in WSL, with drives
+/// mounted under `mount_root`, usually `/mnt`, cleaning it in the process,
+/// like `wslpath -u`. `C:\Users\me\..\you` becomes `/mnt/c/Users/you`.
+/// Relative paths are converted by replacing separators.
+///
+/// Returns `None` for paths that WSL cannot name without knowing the current
+/// drive or directory, like `C:foo` and `\foo`, and for UNC, verbatim, and
+/// device paths.
+///
+/// ```
+/// assert_eq!(lexiclean::to_wsl(r"C:\Users\me", "/mnt").unwrap(), "/mnt/c/Users/me");
+/// ```
+pub fn to_wsl(path: &str, mount_root: &str) -> Option<String> {
+  windows_to_mount(path, &clean_mount_root(mount_root), false)
+}
+
+/// Convert a WSL path under `mount_root` to a Windows path, cleaning it in
+/// the process, like `wslpath -w`. `/mnt/c/Users/me/../you` becomes
+/// `C:\Users\you`. Relative paths are converted by replacing separators.
+///
+/// Returns `None` for absolute paths that are not on a mounted drive, like
+/// `/home/me`.
+///
+/// ```
+/// assert_eq!(lexiclean::from_wsl("/mnt/c/Users/me", "/mnt").unwrap(), r"C:\Users\me");
+/// ```
+pub fn from_wsl(path: &str, mount_root: &str) -> Option<String> {
+  mount_to_windows(path, &clean_mount_root(mount_root))
+}
+
+fn clean_mount_root(mount_root: &str) -> String {
+  let mut mount_root = lexiclean_posix(mount_root);
+
+  if mount_root == "/" {
+    mount_root.clear();
+  }
+
+  mount_root
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn windows_paths_are_converted_to_wsl() {
+    assert_eq!(to_wsl(r"C:\Users\me", "/mnt").unwrap(), "/mnt/c/Users/me");
+    assert_eq!(to_wsl(r"d:/foo/../bar", "/mnt/").unwrap(), "/mnt/d/bar");
+    assert_eq!(to_wsl(r"C:\foo", "/").unwrap(), "/c/foo");
+    assert_eq!(
+      to_wsl(r"C:\foo", "/media//drives").unwrap(),
+      "/media/drives/c/foo"
+    );
+    assert_eq!(to_wsl(r"foo\bar", "/mnt").unwrap(), "foo/bar");
+    assert_eq!(to_wsl(r"\\server\share\foo", "/mnt"), None);
+    assert_eq!(to_wsl(r"C:foo", "/mnt"), None);
+  }
+
+  #[test]
+  fn wsl_paths_are_converted_to_windows() {
+    assert_eq!(from_wsl("/mnt/c/Users/me", "/mnt").unwrap(), r"C:\Users\me");
+    assert_eq!(from_wsl("/mnt/c", "/mnt").unwrap(), r"C:\");
+    assert_eq!(
+      from_wsl("/mnt/c/foo/../../d/bar", "/mnt").unwrap(),
+      r"D:\bar"
+    );
+    assert_eq!(from_wsl("/c/foo", "/").unwrap(), r"C:\foo");
+    assert_eq!(from_wsl("foo/bar", "/mnt").unwrap(), r"foo\bar");
+    assert_eq!(from_wsl("/home/me", "/mnt"), None);
+    assert_eq!(from_wsl("/mnt", "/mnt"), None);
+    assert_eq!(from_wsl("/mntc/foo", "/mnt"), None);
+  }
+}